@@ -167,6 +167,7 @@ pub struct ValidatorBuilder {
     randomize_first_validator_ports: bool,
     publishing_option: Option<VMPublishingOption>,
     template: NodeConfig,
+    validator_templates: Option<Vec<NodeConfig>>,
 }
 
 impl ValidatorBuilder {
@@ -178,6 +179,7 @@ impl ValidatorBuilder {
             randomize_first_validator_ports: true,
             publishing_option: None,
             template: NodeConfig::default_for_validator(),
+            validator_templates: None,
         }
     }
 
@@ -201,6 +203,15 @@ impl ValidatorBuilder {
         self
     }
 
+    /// Use a separate template for each validator, instead of `template` for all of them. This
+    /// determines the number of validators, and the ports of the templates are used as is.
+    pub fn validator_templates(mut self, templates: Vec<NodeConfig>) -> Self {
+        self.num_validators =
+            NonZeroUsize::new(templates.len()).expect("at least one template is required");
+        self.validator_templates = Some(templates);
+        self
+    }
+
     pub fn build<R>(
         mut self,
         mut rng: R,
@@ -283,8 +294,11 @@ impl ValidatorBuilder {
 
         let storage_config = Self::storage_config(&directory);
 
-        let mut validator =
-            ValidatorConfig::new(name, storage_config, directory, self.template.clone());
+        let template = match &self.validator_templates {
+            Some(templates) => templates[index].clone(),
+            None => self.template.clone(),
+        };
+        let mut validator = ValidatorConfig::new(name, storage_config, directory, template);
         Self::initialize_validator_storage(
             &validator,
             rng,
@@ -350,7 +364,8 @@ impl ValidatorBuilder {
             SecureBackend::OnDiskStorage(validator.storage_config.clone());
         config.execution.backend = SecureBackend::OnDiskStorage(validator.storage_config.clone());
 
-        if index > 0 || self.randomize_first_validator_ports {
+        if self.validator_templates.is_none() && (index > 0 || self.randomize_first_validator_ports)
+        {
             config.randomize_ports();
         }
 
@@ -12,6 +12,12 @@ pub struct ConsensusConfig {
     pub contiguous_rounds: u32,
     pub max_block_size: u64,
     pub max_pruned_blocks_in_mem: usize,
+    // Number of timeout certificates preceding the highest one to keep in memory. This history is
+    // carried over when the block store is rebuilt and is kept for debugging purposes only.
+    pub max_timeout_certs_in_mem: usize,
+    // Number of rounds which may be certified without the commit root advancing before a commit
+    // stall is reported. Zero disables the check.
+    pub max_rounds_without_commit: u64,
     // Timeout for consensus to get an ack from mempool for executed transactions (in milliseconds)
     pub mempool_executed_txn_timeout_ms: u64,
     // Timeout for consensus to pull transactions from mempool and get a response (in milliseconds)
@@ -37,6 +43,8 @@ impl Default for ConsensusConfig {
             contiguous_rounds: 2,
             max_block_size: 1000,
             max_pruned_blocks_in_mem: 100,
+            max_timeout_certs_in_mem: 0,
+            max_rounds_without_commit: 20,
             mempool_txn_pull_timeout_ms: 1000,
             mempool_executed_txn_timeout_ms: 1000,
             round_initial_timeout_ms: 1000,
@@ -24,13 +24,20 @@ use consensus_types::{
 use diem_crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, HashValue};
 use diem_infallible::RwLock;
 use diem_logger::prelude::*;
-use diem_types::{ledger_info::LedgerInfoWithSignatures, transaction::TransactionStatus};
+use diem_types::{
+    block_info::BlockInfo, ledger_info::LedgerInfoWithSignatures, transaction::TransactionStatus,
+};
 use executor_types::{Error, StateComputeResult};
 use futures::executor::block_on;
 use short_hex_str::AsShortHexStr;
-#[cfg(test)]
-use std::collections::VecDeque;
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 #[cfg(test)]
 #[path = "block_store_test.rs"]
@@ -80,6 +87,44 @@ fn update_counters_for_committed_blocks(blocks_to_commit: &[Arc<ExecutedBlock>])
     }
 }
 
+/// Bounded retry with exponential backoff for writes to the persistent liveness storage, so that
+/// brief storage unavailability doesn't fail block and certificate inserts. A write which still
+/// fails after `max_retries` retries is reported as an error.
+#[derive(Clone, Copy, Debug)]
+pub struct StorageRetryPolicy {
+    pub max_retries: usize,
+    pub initial_backoff: Duration,
+}
+
+impl StorageRetryPolicy {
+    pub fn no_retry() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: Duration::from_millis(0),
+        }
+    }
+}
+
+impl Default for StorageRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(50),
+        }
+    }
+}
+
+/// A persisted quorum certificate whose certified block info no longer matches the persisted
+/// block it certifies, as found by `BlockStore::audit_qc_consistency`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inconsistency {
+    pub block_id: HashValue,
+    /// The block info certified by the quorum certificate
+    pub certified_block_info: BlockInfo,
+    /// The block info of the persisted block, or None if the block is missing from storage
+    pub persisted_block_info: Option<BlockInfo>,
+}
+
 /// Responsible for maintaining all the blocks of payload and the dependencies of those blocks
 /// (parent and previous QC links).  It is expected to be accessed concurrently by multiple threads
 /// and is thread-safe.
@@ -104,6 +149,13 @@ pub struct BlockStore {
     storage: Arc<dyn PersistentLivenessStorage>,
     /// Used to ensure that any block stored will have a timestamp < the local time
     time_service: Arc<dyn TimeService>,
+    /// How writes to `storage` are retried when they fail
+    storage_retry: StorageRetryPolicy,
+    /// Number of rounds which may be certified on top of the commit root before a commit stall
+    /// is reported, zero disables the check
+    max_rounds_without_commit: u64,
+    /// Whether a commit stall has been reported and the commit root hasn't advanced since
+    commit_stalled: AtomicBool,
 }
 
 pub fn update_counters_and_prune_blocks(
@@ -146,7 +198,10 @@ impl BlockStore {
         initial_data: RecoveryData,
         state_computer: Arc<dyn StateComputer>,
         max_pruned_blocks_in_mem: usize,
+        max_timeout_certs_in_mem: usize,
+        max_rounds_without_commit: u64,
         time_service: Arc<dyn TimeService>,
+        storage_retry: StorageRetryPolicy,
     ) -> Self {
         let highest_tc = initial_data.highest_timeout_certificate();
         let (root, root_metadata, blocks, quorum_certs) = initial_data.take();
@@ -156,10 +211,14 @@ impl BlockStore {
             blocks,
             quorum_certs,
             highest_tc,
+            VecDeque::new(),
             state_computer,
             storage,
             max_pruned_blocks_in_mem,
+            max_timeout_certs_in_mem,
+            max_rounds_without_commit,
             time_service,
+            storage_retry,
         );
         block_on(block_store.try_commit());
         block_store
@@ -168,7 +227,7 @@ impl BlockStore {
     async fn try_commit(&self) {
         // reproduce the same batches (important for the commit phase)
 
-        let mut certs = self.inner.read().get_all_quorum_certs_with_commit_info();
+        let mut certs = self.quorum_certs_with_commit_info();
         certs.sort_unstable_by_key(|qc| qc.commit_info().round());
 
         for qc in certs {
@@ -192,10 +251,14 @@ impl BlockStore {
         blocks: Vec<Block>,
         quorum_certs: Vec<QuorumCert>,
         highest_timeout_cert: Option<TimeoutCertificate>,
+        timeout_cert_history: VecDeque<Arc<TimeoutCertificate>>,
         state_computer: Arc<dyn StateComputer>,
         storage: Arc<dyn PersistentLivenessStorage>,
         max_pruned_blocks_in_mem: usize,
+        max_timeout_certs_in_mem: usize,
+        max_rounds_without_commit: u64,
         time_service: Arc<dyn TimeService>,
+        storage_retry: StorageRetryPolicy,
     ) -> Self {
         let RootInfo(root_block, root_qc, root_ordered_cert, root_commit_li) = root;
 
@@ -242,6 +305,8 @@ impl BlockStore {
             root_commit_li,
             max_pruned_blocks_in_mem,
             highest_timeout_cert.map(Arc::new),
+            timeout_cert_history,
+            max_timeout_certs_in_mem,
         );
 
         let block_store = Self {
@@ -249,6 +314,9 @@ impl BlockStore {
             state_computer,
             storage,
             time_service,
+            storage_retry,
+            max_rounds_without_commit,
+            commit_stalled: AtomicBool::new(false),
         };
         for block in blocks {
             block_store
@@ -332,18 +400,25 @@ impl BlockStore {
         quorum_certs: Vec<QuorumCert>,
     ) {
         let max_pruned_blocks_in_mem = self.inner.read().max_pruned_blocks_in_mem();
-        // Rollover the previous highest TC from the old tree to the new one.
+        let max_timeout_certs_in_mem = self.inner.read().max_timeout_certs_in_mem();
+        // Rollover the previous highest TC and the retained TC history from the old tree to the
+        // new one.
         let prev_htc = self.highest_timeout_cert().map(|tc| tc.as_ref().clone());
+        let prev_tc_history = self.timeout_cert_history();
         let BlockStore { inner, .. } = Self::build(
             root,
             root_metadata,
             blocks,
             quorum_certs,
             prev_htc,
+            prev_tc_history,
             Arc::clone(&self.state_computer),
             Arc::clone(&self.storage),
             max_pruned_blocks_in_mem,
+            max_timeout_certs_in_mem,
+            self.max_rounds_without_commit,
             Arc::clone(&self.time_service),
+            self.storage_retry,
         );
 
         let to_remove = self.inner.read().get_all_block_id();
@@ -394,12 +469,36 @@ impl BlockStore {
         // ensure local time past the block time
         let block_time = Duration::from_micros(executed_block.timestamp_usecs());
         self.time_service.wait_until(block_time);
-        self.storage
-            .save_tree(vec![executed_block.block().clone()], vec![])
-            .context("Insert block failed when saving block")?;
+        self.with_storage_retry(|| {
+            self.storage
+                .save_tree(vec![executed_block.block().clone()], vec![])
+        })
+        .context("Insert block failed when saving block")?;
         self.inner.write().insert_block(executed_block)
     }
 
+    /// Runs the storage write `f`, retrying it with backoff according to `storage_retry`. The
+    /// backoff goes through the time service, like the wait for the block time above.
+    fn with_storage_retry<T>(&self, mut f: impl FnMut() -> anyhow::Result<T>) -> anyhow::Result<T> {
+        let mut backoff = self.storage_retry.initial_backoff;
+        let mut retries = 0;
+        loop {
+            match f() {
+                Err(e) if retries < self.storage_retry.max_retries => {
+                    retries += 1;
+                    warn!(
+                        error = ?e,
+                        retries = retries,
+                        "Failed to persist to consensus db, retrying"
+                    );
+                    self.time_service.sleep(backoff);
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+
     fn execute_block(&self, block: Block) -> anyhow::Result<ExecutedBlock, Error> {
         // Although NIL blocks don't have a payload, we still send a T::default() to compute
         // because we may inject a block prologue transaction.
@@ -436,10 +535,90 @@ impl BlockStore {
             None => bail!("Insert {} without having the block in store first", qc),
         };
 
-        self.storage
-            .save_tree(vec![], vec![qc.clone()])
+        self.with_storage_retry(|| self.storage.save_tree(vec![], vec![qc.clone()]))
             .context("Insert block failed when saving quorum")?;
-        self.inner.write().insert_quorum_cert(qc)
+        self.inner.write().insert_quorum_cert(qc)?;
+        self.check_rounds_without_commit();
+        Ok(())
+    }
+
+    /// Reads the quorum certificates and blocks back from storage and re-checks for every
+    /// certificate that its certified block info matches the persisted block, like
+    /// `insert_single_quorum_cert` does on insert, and returns all mismatches. This catches
+    /// corruption of the consensus db which happened after the certificates were persisted.
+    /// A certificate whose block is missing from storage is only reported if the block is in the
+    /// tree, as the root block (e.g. genesis) is not necessarily persisted.
+    pub fn audit_qc_consistency(&self) -> anyhow::Result<Vec<Inconsistency>> {
+        let (blocks, quorum_certs) = self
+            .storage
+            .retrieve_blocks_and_quorum_certs()
+            .context("Failed to read the consensus db for the audit")?;
+        // Persisted blocks are keyed by the hash of their content, so corrupted blocks are missing
+        let blocks = blocks
+            .into_iter()
+            .map(|block| (block.id(), block))
+            .collect::<HashMap<_, _>>();
+        let tree = self.inner.read();
+        let ordered_root_id = tree.ordered_root().id();
+        let mut inconsistencies = vec![];
+        for qc in quorum_certs {
+            let certified = qc.certified_block();
+            let block_id = certified.id();
+            let persisted_block_info = match blocks.get(&block_id) {
+                Some(block) => {
+                    let block_info = block.gen_block_info(
+                        certified.executed_state_id(),
+                        certified.version(),
+                        certified.next_epoch_state().cloned(),
+                    );
+                    // decoupled execution allows dummy block infos
+                    if block_info.match_ordered_only(certified) {
+                        continue;
+                    }
+                    Some(block_info)
+                }
+                None if block_id != ordered_root_id && tree.get_block(&block_id).is_some() => None,
+                None => continue,
+            };
+            inconsistencies.push(Inconsistency {
+                block_id,
+                certified_block_info: certified.clone(),
+                persisted_block_info,
+            });
+        }
+        Ok(inconsistencies)
+    }
+
+    /// Reports a commit stall if at least `max_rounds_without_commit` rounds have been certified
+    /// on top of the commit root, i.e. consensus is live but blocks are not being committed.
+    /// The stall is reported once, until the commit root catches up again.
+    fn check_rounds_without_commit(&self) {
+        let commit_round = self.commit_root().round();
+        let rounds_without_commit = self
+            .highest_certified_block()
+            .round()
+            .saturating_sub(commit_round);
+        counters::ROUNDS_WITHOUT_COMMIT.set(rounds_without_commit as i64);
+        if self.max_rounds_without_commit == 0 {
+            return;
+        }
+        if rounds_without_commit >= self.max_rounds_without_commit {
+            if !self.commit_stalled.swap(true, Ordering::Relaxed) {
+                counters::COMMIT_STALLS_COUNT.inc();
+                warn!(
+                    commit_round = commit_round,
+                    rounds_without_commit = rounds_without_commit,
+                    "Rounds keep advancing without the commit root moving"
+                );
+            }
+        } else {
+            self.commit_stalled.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns whether a commit stall is currently reported, see `check_rounds_without_commit`.
+    pub fn commit_stalled(&self) -> bool {
+        self.commit_stalled.load(Ordering::Relaxed)
     }
 
     /// Replace the highest timeout certificate in case the given one has a higher round.
@@ -449,13 +628,40 @@ impl BlockStore {
         if tc.round() <= cur_tc_round {
             return Ok(());
         }
-        self.storage
-            .save_highest_timeout_cert(tc.as_ref().clone())
+        self.with_storage_retry(|| self.storage.save_highest_timeout_cert(tc.as_ref().clone()))
             .context("Timeout certificate insert failed when persisting to DB")?;
         self.inner.write().replace_timeout_cert(tc);
         Ok(())
     }
 
+    /// Returns the timeout certificates which have been replaced by a higher one, oldest first.
+    /// At most `max_timeout_certs_in_mem` certificates are retained.
+    pub fn timeout_cert_history(&self) -> VecDeque<Arc<TimeoutCertificate>> {
+        self.inner.read().timeout_cert_history()
+    }
+
+    /// Returns a rough estimate of the memory used by the blocks in the tree, including the
+    /// pruned blocks kept in memory, and their certificates. This is a basis for choosing
+    /// `max_pruned_blocks_in_mem`.
+    pub fn estimated_memory_bytes(&self) -> usize {
+        self.inner.read().estimated_memory_bytes()
+    }
+
+    /// Returns the blocks in the tree which have not been certified by a quorum cert yet,
+    /// ordered by round. This is useful to find out which proposals did not gather enough
+    /// votes when a round is not advancing.
+    pub fn blocks_without_qc(&self) -> Vec<Arc<ExecutedBlock>> {
+        let tree = self.inner.read();
+        let mut blocks: Vec<_> = tree
+            .get_all_block_id()
+            .into_iter()
+            .filter(|id| tree.get_quorum_cert_for_block(id).is_none())
+            .filter_map(|id| tree.get_block(&id))
+            .collect();
+        blocks.sort_by_key(|block| block.round());
+        blocks
+    }
+
     /// Prune the tree up to next_root_id (keep next_root_id's block).  Any branches not part of
     /// the next_root_id's tree should be removed as well.
     ///
@@ -546,6 +752,10 @@ impl BlockReader for BlockStore {
             self.highest_timeout_cert().map(|tc| tc.as_ref().clone()),
         )
     }
+
+    fn quorum_certs_with_commit_info(&self) -> Vec<QuorumCert> {
+        self.inner.read().get_all_quorum_certs_with_commit_info()
+    }
 }
 
 #[cfg(any(test, feature = "fuzzing"))]
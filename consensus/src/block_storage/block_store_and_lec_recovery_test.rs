@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::{block_store::BlockStore, BlockReader},
+    block_storage::{block_store::BlockStore, BlockReader, StorageRetryPolicy},
     persistent_liveness_storage::{LedgerRecoveryData, RecoveryData, RootMetadata},
     state_computer::ExecutionProxy,
     test_utils::{EmptyStorage, TreeInserter},
@@ -81,7 +81,10 @@ fn build_inserter(
             initial_data,
             state_computer,
             10, // max pruned blocks in mem
+            0,  // max timeout certs in mem
+            0,  // max rounds without commit
             Arc::new(SimulatedTimeService::new()),
+            StorageRetryPolicy::no_retry(),
         )),
     )
 }
@@ -2,28 +2,41 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::{block_store::sync_manager::NeedFetchResult, BlockReader},
+    block_storage::{
+        block_store::sync_manager::NeedFetchResult, BlockReader, BlockStore, Inconsistency,
+        StorageRetryPolicy,
+    },
     pending_votes::{PendingVotes, VoteReceptionResult},
-    test_utils::{build_empty_tree, build_simple_tree, TreeInserter},
+    test_utils::{
+        build_empty_tree, build_simple_tree, EmptyStateComputer, EmptyStorage, FlakyStorage,
+        MockStorage, TreeInserter,
+    },
+    util::{mock_time_service::SimulatedTimeService, time_service::TimeService},
 };
 use consensus_types::{
     block::{
         block_test_utils::{
-            self, certificate_for_genesis, placeholder_certificate_for_block,
+            self, certificate_for_genesis, gen_test_certificate, placeholder_certificate_for_block,
             placeholder_ledger_info,
         },
         Block,
     },
     common::Author,
+    executed_block::ExecutedBlock,
+    timeout::Timeout,
+    timeout_certificate::TimeoutCertificate,
     vote::Vote,
     vote_data::VoteData,
 };
 use diem_crypto::{HashValue, PrivateKey};
 use diem_types::{
-    validator_signer::ValidatorSigner, validator_verifier::random_validator_verifier,
+    block_info::BlockInfo,
+    validator_signer::ValidatorSigner,
+    validator_verifier::{random_validator_verifier, ValidatorVerifier},
 };
+use futures::executor::block_on;
 use proptest::prelude::*;
-use std::{cmp::min, collections::HashSet};
+use std::{cmp::min, collections::HashSet, sync::Arc, time::Duration};
 
 #[test]
 fn test_highest_block_and_quorum_cert() {
@@ -200,6 +213,37 @@ fn test_block_store_prune() {
     assert_eq!(block_store.child_links(), block_store.len() - 1);
 }
 
+#[test]
+fn test_prune_tree_removes_non_ancestor_branches() {
+    let mut inserter = TreeInserter::default();
+    let block_store = inserter.block_store();
+    let b0 = block_store
+        .get_block(block_store.ordered_root().id())
+        .unwrap();
+
+    // B0--> B1--> B2
+    //        ╰--> B3--> B4
+    let b1 = inserter.insert_block_with_qc(certificate_for_genesis(), &b0, 1);
+    let b2 = inserter.insert_block(&b1, 2, None);
+    let b3 = inserter.insert_block(&b1, 3, None);
+    let b4 = inserter.insert_block(&b3, 4, None);
+
+    let removed = block_store.prune_tree(b3.id());
+    assert_eq!(
+        removed.into_iter().collect::<HashSet<_>>(),
+        vec![b0.id(), b1.id(), b2.id()].into_iter().collect()
+    );
+    for block in &[&b0, &b1, &b2] {
+        assert!(!block_store.block_exists(block.id()));
+    }
+    for block in &[&b3, &b4] {
+        assert!(block_store.block_exists(block.id()));
+    }
+    assert_eq!(block_store.ordered_root().id(), b3.id());
+    assert_eq!(block_store.len(), 2);
+    assert_eq!(block_store.child_links(), block_store.len() - 1);
+}
+
 #[test]
 fn test_block_tree_gc() {
     // build a tree with 100 nodes, max_pruned_nodes_in_mem = 10
@@ -225,6 +269,27 @@ fn test_block_tree_gc() {
     }
 }
 
+#[test]
+fn test_estimated_memory_bytes() {
+    let mut inserter = TreeInserter::default();
+    let block_store = inserter.block_store();
+    let genesis = block_store.ordered_root();
+    let genesis_estimate = block_store.estimated_memory_bytes();
+    assert!(genesis_estimate >= bcs::to_bytes(genesis.block()).unwrap().len());
+
+    let b1 = inserter.insert_block_with_qc(certificate_for_genesis(), &genesis, 1);
+    let b1_estimate = block_store.estimated_memory_bytes();
+    assert!(b1_estimate >= genesis_estimate + bcs::to_bytes(b1.block()).unwrap().len());
+
+    // Pruned blocks kept in memory still count
+    let b2 = inserter.insert_block(&b1, 2, None);
+    let b2_estimate = block_store.estimated_memory_bytes();
+    assert!(b2_estimate > b1_estimate);
+    block_store.prune_tree(b2.id());
+    assert_eq!(block_store.pruned_blocks_in_mem(), 2);
+    assert_eq!(block_store.estimated_memory_bytes(), b2_estimate);
+}
+
 #[test]
 fn test_path_from_root() {
     let mut inserter = TreeInserter::default();
@@ -254,6 +319,141 @@ fn test_path_from_root() {
     assert_eq!(block_store.path_from_ordered_root(genesis.id()), None);
 }
 
+#[test]
+fn test_blocks_without_qc() {
+    let mut inserter = TreeInserter::default();
+    let block_store = inserter.block_store();
+    let genesis = block_store
+        .get_block(block_store.ordered_root().id())
+        .unwrap();
+    let ids = |blocks: Vec<Arc<ExecutedBlock>>| blocks.iter().map(|b| b.id()).collect::<Vec<_>>();
+
+    // Genesis--> B1--> B2
+    //             ╰--> B3
+    let b1 = inserter.insert_block_with_qc(certificate_for_genesis(), &genesis, 1);
+    let b2 = inserter.insert_block(&b1, 2, None);
+    let b3 = inserter.insert_block(&b1, 3, None);
+    assert_eq!(ids(block_store.blocks_without_qc()), vec![b2.id(), b3.id()]);
+
+    // Extending B2 certifies it.
+    let b4 = inserter.insert_block(&b2, 4, None);
+    assert_eq!(ids(block_store.blocks_without_qc()), vec![b3.id(), b4.id()]);
+}
+
+#[test]
+fn test_quorum_certs_with_commit_info() {
+    let mut inserter = TreeInserter::default();
+    let block_store = inserter.block_store();
+    let genesis = block_store
+        .get_block(block_store.ordered_root().id())
+        .unwrap();
+
+    // Genesis--> B1--> B2--> B3, where the QC for B2 commits genesis
+    let b1 = inserter.insert_block_with_qc(certificate_for_genesis(), &genesis, 1);
+    let b2 = inserter.insert_block(&b1, 2, None);
+    let _b3 = inserter.insert_block(&b2, 3, Some(genesis.block_info()));
+
+    let certs = block_store.quorum_certs_with_commit_info();
+    assert!(certs
+        .iter()
+        .all(|qc| qc.commit_info() != &BlockInfo::empty()));
+    let certified_ids = certs
+        .iter()
+        .map(|qc| qc.certified_block().id())
+        .collect::<HashSet<_>>();
+    assert!(certified_ids.contains(&b2.id()));
+    assert!(!certified_ids.contains(&b1.id()));
+}
+
+#[test]
+fn test_audit_qc_consistency() {
+    let signer = ValidatorSigner::random(None);
+    let validator_verifier = ValidatorVerifier::new_single(signer.author(), signer.public_key());
+    let (initial_data, storage) = MockStorage::start_for_testing((&validator_verifier).into());
+    let block_store = Arc::new(BlockStore::new(
+        storage.clone(),
+        initial_data,
+        Arc::new(EmptyStateComputer),
+        10, // max pruned blocks in mem
+        0,  // max timeout certs in mem
+        0,  // max rounds without commit
+        Arc::new(SimulatedTimeService::new()),
+        StorageRetryPolicy::no_retry(),
+    ));
+    let mut inserter = TreeInserter::new_with_store(signer.clone(), block_store.clone());
+    let genesis = block_store.ordered_root();
+
+    // Genesis--> B1--> B2--> B3
+    let b1 = inserter.insert_block(&genesis, 1, None);
+    let b2 = inserter.insert_block(&b1, 2, None);
+    let _b3 = inserter.insert_block(&b2, 3, None);
+    assert!(block_store.audit_qc_consistency().unwrap().is_empty());
+
+    // Corrupt the persisted QC for B2 so that it certifies the wrong round
+    let info = b2.block_info();
+    let corrupted_info = BlockInfo::new(
+        info.epoch(),
+        7,
+        info.id(),
+        info.executed_state_id(),
+        info.version(),
+        info.timestamp_usecs(),
+        info.next_epoch_state().cloned(),
+    );
+    let corrupted_qc =
+        gen_test_certificate(vec![&signer], corrupted_info.clone(), b1.block_info(), None);
+    storage
+        .shared_storage
+        .qc
+        .lock()
+        .insert(b2.id(), corrupted_qc);
+    // Corrupted blocks no longer deserialize under their id, which looks like a missing block
+    storage.shared_storage.block.lock().remove(&b1.id());
+
+    let inconsistencies: Vec<Inconsistency> = block_store.audit_qc_consistency().unwrap();
+    assert_eq!(inconsistencies.len(), 2);
+    assert!(inconsistencies.contains(&Inconsistency {
+        block_id: b2.id(),
+        certified_block_info: corrupted_info,
+        persisted_block_info: Some(b2.block_info()),
+    }));
+    assert!(inconsistencies.contains(&Inconsistency {
+        block_id: b1.id(),
+        certified_block_info: b1.block_info(),
+        persisted_block_info: None,
+    }));
+}
+
+#[test]
+fn test_commit_stall_detection() {
+    let (initial_data, storage) = EmptyStorage::start_for_testing();
+    let block_store = Arc::new(BlockStore::new(
+        storage,
+        initial_data,
+        Arc::new(EmptyStateComputer),
+        10, // max pruned blocks in mem
+        0,  // max timeout certs in mem
+        3,  // max rounds without commit
+        Arc::new(SimulatedTimeService::new()),
+        StorageRetryPolicy::no_retry(),
+    ));
+    let mut inserter =
+        TreeInserter::new_with_store(ValidatorSigner::random(None), block_store.clone());
+    let genesis = block_store.ordered_root();
+
+    // Genesis--> B1--> B2--> B3, which certifies up to round 2 without a commit
+    let b1 = inserter.insert_block_with_qc(certificate_for_genesis(), &genesis, 1);
+    let b2 = inserter.insert_block(&b1, 2, None);
+    let b3 = inserter.insert_block(&b2, 3, None);
+    assert!(!block_store.commit_stalled());
+
+    // B4 certifies round 3 on top of the genesis commit root
+    let b4 = inserter.insert_block(&b3, 4, None);
+    assert!(block_store.commit_stalled());
+    let _b5 = inserter.insert_block(&b4, 5, None);
+    assert!(block_store.commit_stalled());
+}
+
 #[test]
 fn test_insert_vote() {
     ::diem_logger::Logger::init_for_testing();
@@ -403,3 +603,88 @@ fn test_need_fetch_for_qc() {
         NeedFetchResult::QCAlreadyExist,
     );
 }
+
+#[test]
+fn test_timeout_cert_history_survives_rebuild() {
+    let (initial_data, storage) = EmptyStorage::start_for_testing();
+    let block_store = BlockStore::new(
+        storage,
+        initial_data,
+        Arc::new(EmptyStateComputer),
+        10, // max pruned blocks in mem
+        2,  // max timeout certs in mem
+        0,  // max rounds without commit
+        Arc::new(SimulatedTimeService::new()),
+        StorageRetryPolicy::no_retry(),
+    );
+    for round in 1..=4 {
+        let tc = TimeoutCertificate::new(Timeout::new(1, round));
+        block_store
+            .insert_timeout_certificate(Arc::new(tc))
+            .unwrap();
+    }
+    let history_rounds = |block_store: &BlockStore| {
+        block_store
+            .timeout_cert_history()
+            .iter()
+            .map(|tc| tc.round())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(block_store.highest_timeout_cert().unwrap().round(), 4);
+    assert_eq!(history_rounds(&block_store), vec![2, 3]);
+
+    let (initial_data, _) = EmptyStorage::start_for_testing();
+    let (root, root_metadata, blocks, quorum_certs) = initial_data.take();
+    block_on(block_store.rebuild(root, root_metadata, blocks, quorum_certs));
+    assert_eq!(block_store.highest_timeout_cert().unwrap().round(), 4);
+    assert_eq!(history_rounds(&block_store), vec![2, 3]);
+}
+
+#[test]
+fn test_save_tree_retries_transient_failures() {
+    let build_block_store = |failures: usize| {
+        let (initial_data, storage) = FlakyStorage::start_for_testing(failures);
+        let time_service = Arc::new(SimulatedTimeService::new());
+        let block_store = BlockStore::new(
+            storage.clone(),
+            initial_data,
+            Arc::new(EmptyStateComputer),
+            10, // max pruned blocks in mem
+            0,  // max timeout certs in mem
+            0,  // max rounds without commit
+            time_service.clone(),
+            StorageRetryPolicy {
+                max_retries: 3,
+                initial_backoff: Duration::from_millis(100),
+            },
+        );
+        (block_store, storage, time_service)
+    };
+    let signer = ValidatorSigner::random(None);
+    let new_block = |block_store: &BlockStore| {
+        let genesis = block_store.ordered_root();
+        Block::new_proposal(
+            vec![],
+            1,
+            genesis.timestamp_usecs() + 1,
+            certificate_for_genesis(),
+            &signer,
+        )
+    };
+
+    // A transient failure is retried and the block is inserted
+    let (block_store, storage, time_service) = build_block_store(2);
+    let block = new_block(&block_store);
+    block_store.execute_and_insert_block(block.clone()).unwrap();
+    assert!(block_store.block_exists(block.id()));
+    assert_eq!(storage.save_tree_calls(), 3);
+    // The backoff of 100ms and 200ms is spent in simulated time
+    assert!(time_service.get_current_timestamp() >= Duration::from_millis(300));
+
+    // A persistent failure still errors once the retries are exhausted
+    let (block_store, storage, _) = build_block_store(usize::MAX);
+    let block = new_block(&block_store);
+    assert!(block_store.execute_and_insert_block(block.clone()).is_err());
+    assert!(!block_store.block_exists(block.id()));
+    assert_eq!(storage.save_tree_calls(), 4);
+}
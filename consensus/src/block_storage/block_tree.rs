@@ -10,6 +10,7 @@ use diem_crypto::HashValue;
 use diem_logger::prelude::*;
 use diem_types::{block_info::BlockInfo, ledger_info::LedgerInfoWithSignatures};
 use mirai_annotations::{checked_verify_eq, precondition};
+use serde::Serialize;
 use std::{
     collections::{vec_deque::VecDeque, HashMap, HashSet},
     sync::Arc,
@@ -72,6 +73,10 @@ pub struct BlockTree {
     highest_quorum_cert: Arc<QuorumCert>,
     /// The highest timeout certificate (if any).
     highest_timeout_cert: Option<Arc<TimeoutCertificate>>,
+    /// The timeout certificates replaced by a higher one, oldest first.
+    timeout_cert_history: VecDeque<Arc<TimeoutCertificate>>,
+    /// Num timeout certificates preceding the highest one to keep in memory.
+    max_timeout_certs_in_mem: usize,
     /// The quorum certificate that has highest commit info.
     highest_ordered_cert: Arc<QuorumCert>,
     /// The quorum certificate that has highest commit decision info.
@@ -92,6 +97,8 @@ impl BlockTree {
         root_commit_ledger_info: LedgerInfoWithSignatures,
         max_pruned_blocks_in_mem: usize,
         highest_timeout_cert: Option<Arc<TimeoutCertificate>>,
+        mut timeout_cert_history: VecDeque<Arc<TimeoutCertificate>>,
+        max_timeout_certs_in_mem: usize,
     ) -> Self {
         assert_eq!(
             root.id(),
@@ -112,6 +119,9 @@ impl BlockTree {
         );
 
         let pruned_block_ids = VecDeque::with_capacity(max_pruned_blocks_in_mem);
+        while timeout_cert_history.len() > max_timeout_certs_in_mem {
+            timeout_cert_history.pop_front();
+        }
 
         BlockTree {
             id_to_block,
@@ -120,6 +130,8 @@ impl BlockTree {
             highest_certified_block_id: root_id,
             highest_quorum_cert: Arc::clone(&root_quorum_cert),
             highest_timeout_cert,
+            timeout_cert_history,
+            max_timeout_certs_in_mem,
             highest_ordered_cert: Arc::new(root_ordered_cert),
             highest_ledger_info: root_commit_ledger_info,
             id_to_quorum_cert,
@@ -194,9 +206,26 @@ impl BlockTree {
         self.highest_timeout_cert.clone()
     }
 
-    /// Replace highest timeout cert with the given value.
+    /// Replace highest timeout cert with the given value. The replaced certificate is retained
+    /// in the timeout cert history (up to `max_timeout_certs_in_mem` certificates).
     pub(super) fn replace_timeout_cert(&mut self, tc: Arc<TimeoutCertificate>) {
-        self.highest_timeout_cert.replace(tc);
+        if let Some(prev_tc) = self.highest_timeout_cert.replace(tc) {
+            if self.max_timeout_certs_in_mem > 0 {
+                if self.timeout_cert_history.len() == self.max_timeout_certs_in_mem {
+                    self.timeout_cert_history.pop_front();
+                }
+                self.timeout_cert_history.push_back(prev_tc);
+            }
+        }
+    }
+
+    /// The timeout certificates replaced by a higher one, oldest first.
+    pub(super) fn timeout_cert_history(&self) -> VecDeque<Arc<TimeoutCertificate>> {
+        self.timeout_cert_history.clone()
+    }
+
+    pub(super) fn max_timeout_certs_in_mem(&self) -> usize {
+        self.max_timeout_certs_in_mem
     }
 
     pub(super) fn highest_ordered_cert(&self) -> Arc<QuorumCert> {
@@ -415,6 +444,33 @@ impl BlockTree {
     pub(super) fn get_all_block_id(&self) -> Vec<HashValue> {
         self.id_to_block.keys().cloned().collect()
     }
+
+    /// Returns a rough estimate of the memory used by the blocks in the tree, including the
+    /// pruned blocks kept in memory, and by the certificates, using their serialized sizes as a
+    /// proxy. The execution results of the blocks are not accounted for.
+    pub(super) fn estimated_memory_bytes(&self) -> usize {
+        let blocks: usize = self
+            .id_to_block
+            .values()
+            .map(|block| serialized_size(block.executed_block().block()))
+            .sum();
+        let quorum_certs: usize = self
+            .id_to_quorum_cert
+            .values()
+            .map(|qc| serialized_size(qc.as_ref()))
+            .sum();
+        let timeout_certs: usize = self
+            .highest_timeout_cert
+            .iter()
+            .chain(self.timeout_cert_history.iter())
+            .map(|tc| serialized_size(tc.as_ref()))
+            .sum();
+        blocks + quorum_certs + timeout_certs + serialized_size(&self.highest_ledger_info)
+    }
+}
+
+fn serialized_size<T: Serialize>(value: &T) -> usize {
+    bcs::to_bytes(value).map(|bytes| bytes.len()).unwrap_or(0)
 }
 
 #[cfg(any(test, feature = "fuzzing"))]
@@ -11,7 +11,9 @@ mod block_store;
 mod block_tree;
 pub mod tracing;
 
-pub use block_store::{sync_manager::BlockRetriever, BlockStore};
+pub use block_store::{
+    sync_manager::BlockRetriever, BlockStore, Inconsistency, StorageRetryPolicy,
+};
 use consensus_types::sync_info::SyncInfo;
 use diem_types::ledger_info::LedgerInfoWithSignatures;
 
@@ -58,4 +60,8 @@ pub trait BlockReader: Send + Sync {
 
     /// Return the combination of highest quorum cert, timeout cert and commit cert.
     fn sync_info(&self) -> SyncInfo;
+
+    /// Return all the quorum certificates in the tree which carry commit info, in no
+    /// particular order.
+    fn quorum_certs_with_commit_info(&self) -> Vec<QuorumCert>;
 }
@@ -42,6 +42,25 @@ pub static LAST_COMMITTED_VERSION: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// The number of rounds which have been certified on top of the highest committed block.
+pub static ROUNDS_WITHOUT_COMMIT: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "diem_consensus_rounds_without_commit",
+        "The number of rounds which have been certified on top of the highest committed block."
+    )
+    .unwrap()
+});
+
+/// Count of the times rounds kept advancing without the commit root moving, for at least
+/// `max_rounds_without_commit` rounds.
+pub static COMMIT_STALLS_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "diem_consensus_commit_stalls_count",
+        "Count of the times rounds kept advancing without the commit root moving."
+    )
+    .unwrap()
+});
+
 /// Count of the committed blocks since last restart.
 pub static COMMITTED_BLOCKS_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -222,6 +241,12 @@ pub static WAIT_DURATION_S: Lazy<DurationHistogram> = Lazy::new(|| {
     DurationHistogram::new(register_histogram!("diem_consensus_wait_duration_s", "Histogram of the time it requires to wait before inserting blocks into block store. Measured as the block's timestamp minus the local timestamp.").unwrap())
 });
 
+/// Histogram of the time transactions spent in mempool before being pulled into a proposal.
+/// Measured as the pull time minus the time the transaction was inserted into mempool.
+pub static MEMPOOL_TXN_AGE_AT_PULL_S: Lazy<DurationHistogram> = Lazy::new(|| {
+    DurationHistogram::new(register_histogram!("diem_consensus_mempool_txn_age_at_pull_s", "Histogram of the time transactions spent in mempool before being pulled into a proposal.").unwrap())
+});
+
 ///////////////////
 // CHANNEL COUNTERS
 ///////////////////
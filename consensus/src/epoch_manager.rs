@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::BlockStore,
+    block_storage::{BlockStore, StorageRetryPolicy},
     counters,
     error::{error_kind, DbError},
     liveness::{
@@ -338,7 +338,10 @@ impl EpochManager {
                 recovery_data,
                 self.commit_state_computer.clone(),
                 self.config.max_pruned_blocks_in_mem,
+                self.config.max_timeout_certs_in_mem,
+                self.config.max_rounds_without_commit,
                 Arc::clone(&self.time_service),
+                StorageRetryPolicy::default(),
             ));
 
             info!(epoch = epoch, "Create ProposalGenerator");
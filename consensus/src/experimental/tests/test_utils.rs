@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::BlockStore,
+    block_storage::{BlockStore, StorageRetryPolicy},
     experimental::{
         buffer_manager::ResetAck,
         commit_phase::{CommitChannelType, CommitPhase},
@@ -128,7 +128,10 @@ pub fn prepare_commit_phase_with_block_store_state_computer(
         initial_data,
         block_store_state_computer,
         0, // max pruned blocks in mem
+        0, // max timeout certs in mem
+        0, // max rounds without commit
         time_service,
+        StorageRetryPolicy::no_retry(),
     ));
 
     let mut safety_rules = MetricsSafetyRules::new(safety_rules_manager.client(), storage);
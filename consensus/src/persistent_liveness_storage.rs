@@ -29,6 +29,9 @@ pub trait PersistentLivenessStorage: Send + Sync {
     /// Delete the corresponding blocks and quorum certs atomically.
     fn prune_tree(&self, block_ids: Vec<HashValue>) -> Result<()>;
 
+    /// Read back all the persisted blocks and quorum certs.
+    fn retrieve_blocks_and_quorum_certs(&self) -> Result<(Vec<Block>, Vec<QuorumCert>)>;
+
     /// Persist consensus' state
     fn save_vote(&self, vote: &Vote) -> Result<()>;
 
@@ -315,6 +318,11 @@ impl PersistentLivenessStorage for StorageWriteProxy {
         Ok(())
     }
 
+    fn retrieve_blocks_and_quorum_certs(&self) -> Result<(Vec<Block>, Vec<QuorumCert>)> {
+        let (_, _, blocks, quorum_certs) = self.db.get_data()?;
+        Ok((blocks, quorum_certs))
+    }
+
     fn save_vote(&self, vote: &Vote) -> Result<()> {
         Ok(self.db.save_vote(bcs::to_bytes(vote)?)?)
     }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::BlockStore,
+    block_storage::{BlockStore, StorageRetryPolicy},
     liveness::{
         proposal_generator::ProposalGenerator,
         rotating_proposer_election::RotatingProposer,
@@ -70,7 +70,10 @@ fn build_empty_store(
         initial_data,
         Arc::new(EmptyStateComputer),
         10, // max pruned blocks in mem
+        0,  // max timeout certs in mem
+        0,  // max rounds without commit
         Arc::new(SimulatedTimeService::new()),
+        StorageRetryPolicy::no_retry(),
     ))
 }
 
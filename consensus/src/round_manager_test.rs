@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::{BlockReader, BlockStore},
+    block_storage::{BlockReader, BlockStore, StorageRetryPolicy},
     liveness::{
         proposal_generator::ProposalGenerator,
         proposer_election::ProposerElection,
@@ -192,7 +192,10 @@ impl NodeSetup {
             initial_data,
             state_computer,
             10, // max pruned blocks in mem
+            0,  // max timeout certs in mem
+            0,  // max rounds without commit
             time_service.clone(),
+            StorageRetryPolicy::no_retry(),
         ));
 
         let proposal_generator = ProposalGenerator::new(
@@ -7,7 +7,7 @@ use crate::{
         LedgerRecoveryData, PersistentLivenessStorage, RecoveryData, RootMetadata,
     },
 };
-use anyhow::Result;
+use anyhow::{bail, Result};
 use consensus_types::{
     block::Block, quorum_cert::QuorumCert, timeout_certificate::TimeoutCertificate, vote::Vote,
 };
@@ -20,7 +20,10 @@ use diem_types::{
 };
 use std::{
     collections::{BTreeMap, HashMap},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 use storage_interface::DbReader;
 
@@ -185,6 +188,13 @@ impl PersistentLivenessStorage for MockStorage {
         Ok(())
     }
 
+    fn retrieve_blocks_and_quorum_certs(&self) -> Result<(Vec<Block>, Vec<QuorumCert>)> {
+        Ok((
+            self.shared_storage.block.lock().values().cloned().collect(),
+            self.shared_storage.qc.lock().values().cloned().collect(),
+        ))
+    }
+
     fn save_vote(&self, last_vote: &Vote) -> Result<()> {
         self.shared_storage
             .last_vote
@@ -257,6 +267,10 @@ impl PersistentLivenessStorage for EmptyStorage {
         Ok(())
     }
 
+    fn retrieve_blocks_and_quorum_certs(&self) -> Result<(Vec<Block>, Vec<QuorumCert>)> {
+        Ok((vec![], vec![]))
+    }
+
     fn save_vote(&self, _: &Vote) -> Result<()> {
         Ok(())
     }
@@ -297,3 +311,80 @@ impl PersistentLivenessStorage for EmptyStorage {
         unimplemented!()
     }
 }
+
+/// A storage which fails the first `failures` writes of the tree, and behaves like `EmptyStorage`
+/// otherwise. Used to simulate transient storage unavailability.
+pub struct FlakyStorage {
+    inner: EmptyStorage,
+    failures_left: AtomicUsize,
+    save_tree_calls: AtomicUsize,
+}
+
+impl FlakyStorage {
+    pub fn new(failures: usize) -> Self {
+        Self {
+            inner: EmptyStorage::new(),
+            failures_left: AtomicUsize::new(failures),
+            save_tree_calls: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn start_for_testing(failures: usize) -> (RecoveryData, Arc<Self>) {
+        let storage = Arc::new(FlakyStorage::new(failures));
+        let recovery_data = storage
+            .start()
+            .expect_recovery_data("Flaky storage should never fail constructing recovery data");
+        (recovery_data, storage)
+    }
+
+    /// The number of times `save_tree` has been called, including the failed calls.
+    pub fn save_tree_calls(&self) -> usize {
+        self.save_tree_calls.load(Ordering::SeqCst)
+    }
+}
+
+impl PersistentLivenessStorage for FlakyStorage {
+    fn save_tree(&self, blocks: Vec<Block>, quorum_certs: Vec<QuorumCert>) -> Result<()> {
+        self.save_tree_calls.fetch_add(1, Ordering::SeqCst);
+        let failed = self
+            .failures_left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if failed {
+            bail!("storage temporarily unavailable");
+        }
+        self.inner.save_tree(blocks, quorum_certs)
+    }
+
+    fn prune_tree(&self, block_ids: Vec<HashValue>) -> Result<()> {
+        self.inner.prune_tree(block_ids)
+    }
+
+    fn retrieve_blocks_and_quorum_certs(&self) -> Result<(Vec<Block>, Vec<QuorumCert>)> {
+        self.inner.retrieve_blocks_and_quorum_certs()
+    }
+
+    fn save_vote(&self, vote: &Vote) -> Result<()> {
+        self.inner.save_vote(vote)
+    }
+
+    fn recover_from_ledger(&self) -> LedgerRecoveryData {
+        self.inner.recover_from_ledger()
+    }
+
+    fn start(&self) -> LivenessStorageData {
+        self.inner.start()
+    }
+
+    fn save_highest_timeout_cert(&self, highest_timeout_cert: TimeoutCertificate) -> Result<()> {
+        self.inner.save_highest_timeout_cert(highest_timeout_cert)
+    }
+
+    fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
+        self.inner.retrieve_epoch_change_proof(version)
+    }
+
+    fn diem_db(&self) -> Arc<dyn DbReader> {
+        self.inner.diem_db()
+    }
+}
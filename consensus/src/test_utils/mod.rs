@@ -1,7 +1,7 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::block_storage::{BlockReader, BlockStore};
+use crate::block_storage::{BlockReader, BlockStore, StorageRetryPolicy};
 use consensus_types::{
     block::{block_test_utils::certificate_for_genesis, Block},
     common::Round,
@@ -26,7 +26,7 @@ use diem_types::block_info::BlockInfo;
 pub use mock_state_computer::{
     EmptyStateComputer, MockStateComputer, RandomComputeResultStateComputer,
 };
-pub use mock_storage::{EmptyStorage, MockSharedStorage, MockStorage};
+pub use mock_storage::{EmptyStorage, FlakyStorage, MockSharedStorage, MockStorage};
 pub use mock_txn_manager::MockTransactionManager;
 
 pub const TEST_TIMEOUT: Duration = Duration::from_secs(60);
@@ -66,7 +66,10 @@ pub fn build_empty_tree() -> Arc<BlockStore> {
         initial_data,
         Arc::new(EmptyStateComputer),
         10, // max pruned blocks in mem
+        0,  // max timeout certs in mem
+        0,  // max rounds without commit
         Arc::new(SimulatedTimeService::new()),
+        StorageRetryPolicy::no_retry(),
     ))
 }
 
@@ -1,7 +1,7 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{error::MempoolError, state_replication::TxnManager};
+use crate::{counters, error::MempoolError, state_replication::TxnManager};
 use anyhow::{format_err, Result};
 use consensus_types::{block::Block, common::Payload};
 use diem_logger::prelude::*;
@@ -14,7 +14,7 @@ use executor_types::StateComputeResult;
 use fail::fail_point;
 use futures::channel::{mpsc, oneshot};
 use itertools::Itertools;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tokio::time::{sleep, timeout};
 
 const NO_TXN_DELAY: u64 = 30;
@@ -74,7 +74,15 @@ impl MempoolProxy {
                 Err(anyhow::anyhow!("[consensus] did not receive GetBlockResponse on time").into())
             }
             Ok(resp) => match resp.map_err(anyhow::Error::from)?? {
-                ConsensusResponse::GetBlockResponse(txns) => Ok(txns),
+                ConsensusResponse::GetBlockResponse(txns, insertion_times) => {
+                    let now = SystemTime::now();
+                    for insertion_time in insertion_times.into_iter().flatten() {
+                        if let Ok(age) = now.duration_since(insertion_time) {
+                            counters::MEMPOOL_TXN_AGE_AT_PULL_S.observe_duration(age);
+                        }
+                    }
+                    Ok(txns)
+                }
                 _ => Err(
                     anyhow::anyhow!("[consensus] did not receive expected GetBlockResponse").into(),
                 ),
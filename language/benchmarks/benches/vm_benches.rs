@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use criterion::{criterion_group, criterion_main, measurement::Measurement, Criterion};
-use language_benchmarks::{measurement::cpu_time_measurement, move_vm::bench};
+use language_benchmarks::{
+    measurement::cpu_time_measurement,
+    move_vm::{bench, bench_with_module_copies},
+};
 
 //
 // MoveVM benchmarks
@@ -16,6 +19,10 @@ fn call<M: Measurement + 'static>(c: &mut Criterion<M>) {
     bench(c, "call");
 }
 
+fn call_with_many_modules<M: Measurement + 'static>(c: &mut Criterion<M>) {
+    bench_with_module_copies(c, "call", 100);
+}
+
 fn natives<M: Measurement + 'static>(c: &mut Criterion<M>) {
     bench(c, "natives");
 }
@@ -25,6 +32,7 @@ criterion_group!(
     config = cpu_time_measurement();
     targets = arith,
     call,
+    call_with_many_modules,
     natives
 );
 
@@ -2,18 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use criterion::{measurement::Measurement, Criterion};
-use move_binary_format::CompiledModule;
+use move_binary_format::{file_format::IdentifierIndex, CompiledModule};
 use move_core_types::{
     account_address::AccountAddress,
+    gas_schedule::GasAlgebra,
     identifier::{IdentStr, Identifier},
     language_storage::{ModuleId, CORE_CODE_ADDRESS},
 };
 use move_lang::{compiled_unit::CompiledUnit, Compiler, Flags};
 use move_vm_runtime::move_vm::MoveVM;
 use move_vm_test_utils::BlankStorage;
-use move_vm_types::gas_schedule::GasStatus;
+use move_vm_types::gas_schedule::{GasStatus, INITIAL_GAS_SCHEDULE};
 use once_cell::sync::Lazy;
-use std::path::PathBuf;
+use std::{env, path::PathBuf};
+
+/// If this environment variable is set, the gas consumed by each benchmarked function is
+/// reported next to Criterion's timings. Unlike timings, gas is independent of the machine the
+/// benchmark runs on, which makes it suitable for tracking regressions in CI.
+const REPORT_GAS_ENV_VAR: &str = "MOVE_BENCH_REPORT_GAS";
 
 static MOVE_BENCH_SRC_PATH: Lazy<PathBuf> = Lazy::new(|| {
     vec![env!("CARGO_MANIFEST_DIR"), "src", "bench.move"]
@@ -23,12 +29,33 @@ static MOVE_BENCH_SRC_PATH: Lazy<PathBuf> = Lazy::new(|| {
 
 /// Entry point for the bench, provide a function name to invoke in Module Bench in bench.move.
 pub fn bench<M: Measurement + 'static>(c: &mut Criterion<M>, fun: &str) {
-    let modules = compile_modules();
+    bench_with_module_copies(c, fun, 0);
+}
+
+/// Like `bench`, but publishes `num_copies` copies of the Bench module under different names
+/// before benchmarking, so that the function is called with many modules in the VM's cache.
+pub fn bench_with_module_copies<M: Measurement + 'static>(
+    c: &mut Criterion<M>,
+    fun: &str,
+    num_copies: usize,
+) {
+    let mut modules = compile_modules();
+    let bench_module = modules
+        .iter()
+        .find(|module| module.self_id().name().as_str() == "Bench")
+        .expect("bench.move must define module Bench")
+        .clone();
+    modules.extend((0..num_copies).map(|i| renamed_module(&bench_module, &format!("Bench{}", i))));
     let move_vm = MoveVM::new(move_stdlib::natives::all_natives(
         AccountAddress::from_hex_literal("0x1").unwrap(),
     ))
     .unwrap();
-    execute(c, &move_vm, modules, fun);
+    let bench_name = if num_copies == 0 {
+        fun.to_string()
+    } else {
+        format!("{}_with_{}_module_copies", fun, num_copies)
+    };
+    execute(c, &move_vm, modules, fun, &bench_name);
 }
 
 // Compile `bench.move` and its dependencies
@@ -49,12 +76,25 @@ fn compile_modules() -> Vec<CompiledModule> {
         .collect()
 }
 
+// Copy `module` with its own name replaced by `name`
+fn renamed_module(module: &CompiledModule, name: &str) -> CompiledModule {
+    let mut module = module.clone();
+    module
+        .identifiers
+        .push(Identifier::new(name).expect("Invalid module name"));
+    let self_handle = module.self_module_handle_idx.0 as usize;
+    module.module_handles[self_handle].name =
+        IdentifierIndex((module.identifiers.len() - 1) as u16);
+    module
+}
+
 // execute a given function in the Bench module
 fn execute<M: Measurement + 'static>(
     c: &mut Criterion<M>,
     move_vm: &MoveVM,
     modules: Vec<CompiledModule>,
     fun: &str,
+    bench_name: &str,
 ) {
     // establish running context
     let storage = BlankStorage::new();
@@ -76,8 +116,30 @@ fn execute<M: Measurement + 'static>(
     let module_id = ModuleId::new(sender, Identifier::new("Bench").unwrap());
     let fun_name = IdentStr::new(fun).unwrap_or_else(|_| panic!("Invalid identifier name {}", fun));
 
+    if env::var_os(REPORT_GAS_ENV_VAR).is_some() {
+        let mut metered_status = GasStatus::new(
+            &INITIAL_GAS_SCHEDULE,
+            INITIAL_GAS_SCHEDULE
+                .gas_constants
+                .maximum_number_of_gas_units,
+        );
+        let gas_before = metered_status.remaining_gas().get();
+        session
+            .execute_function(&module_id, fun_name, vec![], vec![], &mut metered_status)
+            .unwrap_or_else(|err| {
+                panic!(
+                    "{:?}::{} failed with {:?}",
+                    &module_id,
+                    fun,
+                    err.into_vm_status()
+                )
+            });
+        let gas_used = gas_before - metered_status.remaining_gas().get();
+        println!("{}: {} gas units", bench_name, gas_used);
+    }
+
     // benchmark
-    c.bench_function(fun, |b| {
+    c.bench_function(bench_name, |b| {
         b.iter(|| {
             session
                 .execute_function(&module_id, fun_name, vec![], vec![], &mut gas_status)
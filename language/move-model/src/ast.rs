@@ -517,6 +517,56 @@ impl ExpData {
         vars
     }
 
+    /// Returns the declarations of local variables which are bound in this expression by a
+    /// `let`, a quantifier, or a lambda, but never used in their scope.
+    pub fn unused_bindings(&self, env: &GlobalEnv) -> Vec<LocalVarDecl> {
+        let is_used = |sym: Symbol, scope: &[&Exp]| {
+            scope
+                .iter()
+                .any(|e| e.free_vars(env).iter().any(|(s, _)| *s == sym))
+        };
+        let mut unused = vec![];
+        let mut visitor = |e: &ExpData| {
+            use ExpData::*;
+            match e {
+                Lambda(_, decls, body) => {
+                    for decl in decls {
+                        if !is_used(decl.name, &[body]) {
+                            unused.push(decl.clone());
+                        }
+                    }
+                }
+                Block(_, decls, body) => {
+                    for (i, decl) in decls.iter().enumerate() {
+                        // A binding is in scope of the bindings following it, and of the body.
+                        let mut scope = decls[i + 1..]
+                            .iter()
+                            .filter_map(|d| d.binding.as_ref())
+                            .collect_vec();
+                        scope.push(body);
+                        if !is_used(decl.name, &scope) {
+                            unused.push(decl.clone());
+                        }
+                    }
+                }
+                Quant(_, _, ranges, triggers, condition, body) => {
+                    for (i, (decl, _)) in ranges.iter().enumerate() {
+                        let mut scope = ranges[i + 1..].iter().map(|(_, r)| r).collect_vec();
+                        scope.extend(triggers.iter().flatten());
+                        scope.extend(condition.iter());
+                        scope.push(body);
+                        if !is_used(decl.name, &scope) {
+                            unused.push(decl.clone());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        };
+        self.visit(&mut visitor);
+        unused
+    }
+
     /// Returns the used memory of this expression.
     pub fn used_memory(
         &self,
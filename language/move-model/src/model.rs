@@ -25,7 +25,7 @@ use std::{
 
 use codespan::{ByteIndex, ByteOffset, ColumnOffset, FileId, Files, LineOffset, Location, Span};
 use codespan_reporting::{
-    diagnostic::{Diagnostic, Label, Severity},
+    diagnostic::{Diagnostic, Label, LabelStyle, Severity},
     term::{emit, termcolor::WriteColor, Config},
 };
 use itertools::Itertools;
@@ -825,6 +825,21 @@ impl GlobalEnv {
         self.diag_count(Severity::Error)
     }
 
+    /// Returns the location of the primary label together with the message of each diagnostic
+    /// of given or higher severity. Diagnostics without a primary label are skipped.
+    pub fn get_diag_locs(&self, min_severity: Severity) -> Vec<(Loc, String)> {
+        self.diags
+            .borrow()
+            .iter()
+            .filter(|(d, _)| d.severity >= min_severity)
+            .filter_map(|(d, _)| {
+                let label = d.labels.iter().find(|l| l.style == LabelStyle::Primary)?;
+                let span = Span::new(label.range.start as u32, label.range.end as u32);
+                Some((Loc::new(label.file_id, span), d.message.clone()))
+            })
+            .collect()
+    }
+
     /// Returns true if diagnostics have warning severity or worse.
     pub fn has_warnings(&self) -> bool {
         self.diags
@@ -3,7 +3,13 @@
 
 //! Wrapper around the boogie program. Allows to call boogie and analyze the output.
 
-use std::{collections::BTreeMap, fs, num::ParseIntError, option::Option::None};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    num::ParseIntError,
+    option::Option::None,
+    time::Duration,
+};
 
 use anyhow::anyhow;
 use codespan::{ByteIndex, ColumnIndex, LineIndex, Location, Span};
@@ -15,12 +21,15 @@ use once_cell::sync::Lazy;
 use pretty::RcDoc;
 use regex::Regex;
 
-use bytecode::function_target_pipeline::{FunctionTargetsHolder, FunctionVariant};
+use bytecode::{
+    function_target_pipeline::{FunctionTargetsHolder, FunctionVariant},
+    global_invariant_instrumentation_v2::GLOBAL_INVARIANT_FAILS_MESSAGE,
+};
 use move_binary_format::file_format::FunctionDefinitionIndex;
 use move_model::{
     ast::TempIndex,
     code_writer::CodeWriter,
-    model::{FunId, GlobalEnv, Loc, ModuleId, NodeId, QualifiedId, StructId},
+    model::{FunId, FunctionEnv, GlobalEnv, Loc, ModuleId, NodeId, QualifiedId, StructId},
     ty::{PrimitiveType, Type},
 };
 
@@ -57,7 +66,7 @@ pub struct BoogieOutput {
 }
 
 /// Kind of boogie error.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum BoogieErrorKind {
     Assertion,
     Inconclusive,
@@ -81,6 +90,18 @@ pub struct BoogieError {
     pub model: Option<Model>,
 }
 
+impl BoogieError {
+    /// Returns the function which was being verified when this error was found. This is
+    /// identified by the first location in the execution trace which belongs to a function, as
+    /// the location of the error itself may be in a spec block outside of the function.
+    pub fn verified_function<'env>(&self, env: &'env GlobalEnv) -> Option<FunctionEnv<'env>> {
+        self.execution_trace.iter().find_map(|entry| match entry {
+            TraceEntry::AtLocation(loc) => env.get_enclosing_function(loc),
+            _ => None,
+        })
+    }
+}
+
 /// A trace entry.
 pub enum TraceEntry {
     AtLocation(Loc),
@@ -102,6 +123,31 @@ static INCONCLUSIVE_DIAG_STARTS: Lazy<Regex> = Lazy::new(|| {
 static INCONSISTENCY_DIAG_STARTS: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^inconsistency_detected\((?P<args>[^)]*)\)").unwrap());
 
+// Per-procedure timing, as printed by boogie with `-trace`
+static PROCEDURE_TIMING: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?m)^Verifying (?P<proc>\S+) \.\.\.$|^\s*\[(?P<secs>[0-9.]+) s, \d+ proof obligations?\]",
+    )
+    .unwrap()
+});
+
+impl BoogieOutput {
+    /// Returns the time boogie spent on each procedure, in the order they were verified. This
+    /// is only available if boogie was called with `-trace`, otherwise the result is empty.
+    pub fn procedure_times(&self) -> Vec<(String, Duration)> {
+        let mut times = vec![];
+        let mut current = None;
+        for cap in PROCEDURE_TIMING.captures_iter(&self.all_output) {
+            if let Some(proc) = cap.name("proc") {
+                current = Some(proc.as_str().to_string());
+            } else if let (Some(proc), Ok(secs)) = (current.take(), cap["secs"].parse::<f64>()) {
+                times.push((proc, Duration::from_secs_f64(secs)));
+            }
+        }
+        times
+    }
+}
+
 impl<'env> BoogieWrapper<'env> {
     /// Calls boogie on the given file. On success, returns a struct representing the analyzed
     /// output of boogie.
@@ -210,30 +256,131 @@ impl<'env> BoogieWrapper<'env> {
     }
 
     /// Calls boogie and analyzes output.
-    pub fn call_boogie_and_verify_output(&self, boogie_file: &str) -> anyhow::Result<()> {
-        let BoogieOutput { errors, all_output } = self.call_boogie(boogie_file)?;
+    pub fn call_boogie_and_verify_output(&self, boogie_file: &str) -> anyhow::Result<BoogieOutput> {
+        let BoogieOutput {
+            mut errors,
+            all_output,
+        } = self.call_boogie(boogie_file)?;
+        if self.options.sort_diagnostics || self.options.num_instances > 1 {
+            // Which instance finishes first is nondeterministic, and so is the order in which
+            // its errors are reported. Sort them to make the output independent of it.
+            errors.sort_by(|e1, e2| (&e1.loc, &e1.message).cmp(&(&e2.loc, &e2.message)));
+        }
         let boogie_log_file = self.options.get_boogie_log_file(boogie_file);
         let log_file_existed = std::path::Path::new(&boogie_log_file).exists();
         debug!("writing boogie log to {}", boogie_log_file);
         fs::write(&boogie_log_file, &all_output)?;
 
-        for error in &errors {
-            self.add_error(error);
+        if self.options.report_invariants_separately {
+            let (inv_errors, other_errors): (Vec<_>, Vec<_>) = errors
+                .iter()
+                .partition(|e| e.message == GLOBAL_INVARIANT_FAILS_MESSAGE);
+            for error in other_errors {
+                self.add_error(error);
+            }
+            self.add_invariant_errors(&inv_errors);
+        } else {
+            for error in &errors {
+                self.add_error(error);
+            }
+        }
+
+        if self.options.check_determinism {
+            self.check_determinism(boogie_file, &errors)?;
         }
 
         if !log_file_existed && !self.options.keep_artifacts {
             std::fs::remove_file(boogie_log_file).unwrap_or_default();
         }
 
+        Ok(BoogieOutput { errors, all_output })
+    }
+
+    /// Calls boogie repeatedly on the same file and compares the reported errors with the ones
+    /// from the initial run. Reports an error for each function whose verification result is
+    /// not stable across runs.
+    fn check_determinism(
+        &self,
+        boogie_file: &str,
+        initial_errors: &[BoogieError],
+    ) -> anyhow::Result<()> {
+        let error_keys = |errors: &[BoogieError]| {
+            errors
+                .iter()
+                .map(|e| (e.kind, e.loc.clone(), e.message.clone()))
+                .collect::<BTreeSet<_>>()
+        };
+        let runs = std::cmp::max(2, self.options.bench_repeat);
+        let initial = error_keys(initial_errors);
+        let mut unstable_locs = BTreeSet::new();
+        for run in 1..runs {
+            debug!("checking determinism: boogie run {} of {}", run + 1, runs);
+            let BoogieOutput { errors, .. } = self.call_boogie(boogie_file)?;
+            let current = error_keys(&errors);
+            unstable_locs.extend(
+                initial
+                    .symmetric_difference(&current)
+                    .map(|(_, loc, _)| loc.clone()),
+            );
+        }
+
+        // Group unstable results by the function they belong to.
+        let mut unstable_funs = BTreeMap::new();
+        for loc in unstable_locs {
+            let (name, loc) = match self.env.get_enclosing_function(&loc) {
+                Some(fun) => (fun.get_full_name_str(), fun.get_loc()),
+                None => ("<unknown>".to_string(), loc),
+            };
+            unstable_funs.entry(name).or_insert(loc);
+        }
+        for (name, loc) in unstable_funs {
+            self.env.error(
+                &loc,
+                &format!(
+                    "verification result of `{}` is not deterministic across {} prover runs",
+                    name, runs
+                ),
+            );
+        }
         Ok(())
     }
 
+    /// Adds failures of global invariants, grouped by the invariant which failed. The message
+    /// of each error names the function in whose context the invariant failed.
+    fn add_invariant_errors(&self, errors: &[&BoogieError]) {
+        let mut by_invariant: BTreeMap<Loc, Vec<&BoogieError>> = BTreeMap::new();
+        for error in errors {
+            by_invariant
+                .entry(error.loc.clone())
+                .or_default()
+                .push(error);
+        }
+        for errors in by_invariant.values() {
+            for error in errors {
+                let message = match error.verified_function(self.env) {
+                    Some(fun_env) => format!(
+                        "{} in context of function `{}`",
+                        error.message,
+                        fun_env.get_full_name_str()
+                    ),
+                    None => error.message.clone(),
+                };
+                self.add_error_with_message(error, message);
+            }
+        }
+    }
+
     /// Helper to add a boogie error as a codespan Diagnostic.
     fn add_error(&self, error: &BoogieError) {
+        self.add_error_with_message(error, error.message.clone())
+    }
+
+    /// Helper to add a boogie error as a codespan Diagnostic, using `message` as the message.
+    fn add_error_with_message(&self, error: &BoogieError, message: String) {
         // Create the error
         let label = Label::primary(error.loc.file_id(), error.loc.span());
         let mut diag = Diagnostic::error()
-            .with_message(error.message.clone())
+            .with_message(message)
             .with_labels(vec![label]);
 
         // Now add trace diagnostics.
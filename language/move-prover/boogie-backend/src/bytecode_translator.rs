@@ -13,6 +13,7 @@ use bytecode::{
     function_target::FunctionTarget,
     function_target_pipeline::{FunctionTargetsHolder, VerificationFlavor},
     mono_analysis,
+    options::ProverOptions,
     stackless_bytecode::{BorrowEdge, BorrowNode, Bytecode, Constant, HavocKind, Operation},
 };
 use move_model::{
@@ -411,9 +412,17 @@ impl<'env> FunctionTranslator<'env> {
                     self.type_inst.is_empty(),
                     "verification variant cannot have an instantiation"
                 );
-                let timeout = fun_target
-                    .func_env
-                    .get_num_pragma(TIMEOUT_PRAGMA, || options.vc_timeout);
+                // A timeout override given on the command line takes precedence over both the
+                // pragma and the global default.
+                let timeout = match ProverOptions::get(self.parent.env)
+                    .timeout_overrides
+                    .get(&fun_target.func_env.get_full_name_str())
+                {
+                    Some(timeout) => *timeout,
+                    None => fun_target
+                        .func_env
+                        .get_num_pragma(TIMEOUT_PRAGMA, || options.vc_timeout),
+                };
 
                 let mut attribs = vec![format!("{{:timeLimit {}}} ", timeout)];
 
@@ -6,7 +6,7 @@ use itertools::Itertools;
 use move_command_line_common::env::{read_bool_env_var, read_env_var};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use std::{collections::BTreeMap, process::Command};
 
 /// Default flags passed to boogie. Additional flags will be added to this via the -B option.
 const DEFAULT_BOOGIE_FLAGS: &[&str] = &[
@@ -21,6 +21,32 @@ const MIN_BOOGIE_VERSION: &str = "2.9.0";
 const MIN_Z3_VERSION: &str = "4.8.9";
 const EXPECTED_CVC4_VERSION: &str = "aac53f51";
 
+/// The executables of boogie and of the solver which are used for verification, as resolved
+/// from the options, together with their versions if these could be determined.
+#[derive(Debug, Clone, Serialize)]
+pub struct SolverInfo {
+    pub boogie_exe: String,
+    pub boogie_version: Option<String>,
+    pub solver: String,
+    pub solver_exe: String,
+    pub solver_version: Option<String>,
+}
+
+impl std::fmt::Display for SolverInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let version = |v: &Option<String>| v.clone().unwrap_or_else(|| "unknown".to_string());
+        write!(
+            f,
+            "boogie `{}` (version {}) with {} `{}` (version {})",
+            self.boogie_exe,
+            version(&self.boogie_version),
+            self.solver,
+            self.solver_exe,
+            version(&self.solver_version)
+        )
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum VectorTheory {
     BoogieArray,
@@ -77,6 +103,16 @@ pub struct BoogieOptions {
     /// How many times to call the prover backend for the verification problem. This is used for
     /// benchmarking.
     pub bench_repeat: usize,
+    /// Whether to call the prover backend repeatedly (at least twice, or `bench_repeat` times)
+    /// and report functions whose verification result is not the same across runs.
+    pub check_determinism: bool,
+    /// Whether to report failures of global invariants grouped by invariant, separately from
+    /// the other verification errors.
+    pub report_invariants_separately: bool,
+    /// Whether to report verification errors sorted by location instead of in the order in
+    /// which the solver reports them. This is always done if `num_instances > 1`, since then
+    /// the reported order depends on which instance finishes first.
+    pub sort_diagnostics: bool,
     /// Whether to use the sequence theory as the internal representation for $Vector type.
     pub vector_using_sequences: bool,
     /// A seed for the prover.
@@ -104,12 +140,18 @@ pub struct BoogieOptions {
     pub vector_theory: VectorTheory,
     /// Whether to generate a z3 trace file and where to put it.
     pub z3_trace_file: Option<String>,
+    /// Options passed on to the SMT solver, as key/value pairs. These are not checked
+    /// by the prover, so an invalid option will only be reported by the solver.
+    pub solver_options: BTreeMap<String, String>,
 }
 
 impl Default for BoogieOptions {
     fn default() -> Self {
         Self {
             bench_repeat: 1,
+            check_determinism: false,
+            report_invariants_separately: false,
+            sort_diagnostics: false,
             boogie_exe: read_env_var("BOOGIE_EXE"),
             use_exp_boogie: false,
             z3_exe: read_env_var("Z3_EXE"),
@@ -138,6 +180,7 @@ impl Default for BoogieOptions {
             hard_timeout_secs: 0,
             vector_theory: VectorTheory::BoogieArray,
             z3_trace_file: None,
+            solver_options: BTreeMap::new(),
         }
     }
 }
@@ -208,6 +251,9 @@ impl BoogieOptions {
         //add(&["-proverOpt:VERBOSITY=3"]);
         //add(&["-proverOpt:C:-st"]);
 
+        for (key, value) in &self.solver_options {
+            add(&[&format!("-proverOpt:O:{}={}", key, value)]);
+        }
         if let Some(file) = &self.z3_trace_file {
             add(&[
                 "-proverOpt:O:trace=true",
@@ -240,6 +286,38 @@ impl BoogieOptions {
         }
     }
 
+    /// Returns the boogie and solver executables which are used for verification, and their
+    /// versions. This reports the experimental boogie if `use_exp_boogie` is set.
+    pub fn solver_info(&self) -> SolverInfo {
+        let boogie_exe = if self.use_exp_boogie {
+            read_env_var("EXP_BOOGIE_EXE")
+        } else {
+            self.boogie_exe.clone()
+        };
+        let boogie_version =
+            Self::get_version("boogie", &boogie_exe, &["-version"], r"version ([0-9.]*)").ok();
+        let (solver, solver_exe, solver_version) = if self.use_cvc4 {
+            let version = Self::get_version(
+                "cvc4",
+                &self.cvc4_exe,
+                &["--version"],
+                r"git master ([0-9a-f]*)",
+            );
+            ("cvc4", self.cvc4_exe.clone(), version.ok())
+        } else {
+            let version =
+                Self::get_version("z3", &self.z3_exe, &["--version"], r"version ([0-9.]*)");
+            ("z3", self.z3_exe.clone(), version.ok())
+        };
+        SolverInfo {
+            boogie_exe,
+            boogie_version,
+            solver: solver.to_string(),
+            solver_exe,
+            solver_version,
+        }
+    }
+
     /// Checks whether the expected tool versions are installed in the environment.
     pub fn check_tool_versions(&self) -> anyhow::Result<()> {
         if !self.boogie_exe.is_empty() {
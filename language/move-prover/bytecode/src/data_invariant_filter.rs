@@ -0,0 +1,56 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transformation which restricts verification to data invariants, for the
+//! `verify_struct_invariants_only` option.
+//!
+//! This removes every assertion from verified functions which has not been introduced by the
+//! data invariant instrumentation, i.e. the assertions of `ensures`, `aborts_if`, inline spec
+//! blocks, and global invariants. It must run after all instrumentation which introduces
+//! assertions. Assumptions are left in place, as data invariants may depend on them.
+
+use crate::{
+    data_invariant_instrumentation::INVARIANT_FAILS_MESSAGE,
+    function_target::FunctionData,
+    function_target_pipeline::{FunctionTargetProcessor, FunctionTargetsHolder},
+    stackless_bytecode::{AttrId, Bytecode, PropKind},
+};
+use move_model::model::FunctionEnv;
+
+pub struct DataInvariantFilterProcessor {}
+
+impl DataInvariantFilterProcessor {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+impl FunctionTargetProcessor for DataInvariantFilterProcessor {
+    fn process(
+        &self,
+        _targets: &mut FunctionTargetsHolder,
+        fun_env: &FunctionEnv<'_>,
+        mut data: FunctionData,
+    ) -> FunctionData {
+        if fun_env.is_native() || fun_env.is_intrinsic() || !data.variant.is_verified() {
+            // Nothing to do.
+            return data;
+        }
+        let vc_infos = std::mem::take(&mut data.vc_infos);
+        let is_data_invariant = |id: &AttrId| {
+            vc_infos
+                .get(id)
+                .map(|msg| msg == INVARIANT_FAILS_MESSAGE)
+                .unwrap_or(false)
+        };
+        data.code.retain(
+            |bc| !matches!(bc, Bytecode::Prop(id, PropKind::Assert, _) if !is_data_invariant(id)),
+        );
+        data.vc_infos = vc_infos;
+        data
+    }
+
+    fn name(&self) -> String {
+        "data_invariant_filter".to_string()
+    }
+}
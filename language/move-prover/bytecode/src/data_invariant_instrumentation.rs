@@ -26,7 +26,7 @@ use move_model::{
     ty::Type,
 };
 
-const INVARIANT_FAILS_MESSAGE: &str = "data invariant does not hold";
+pub(crate) const INVARIANT_FAILS_MESSAGE: &str = "data invariant does not hold";
 
 pub struct DataInvariantInstrumentationProcessor {}
 
@@ -31,7 +31,7 @@ use move_model::{
 };
 use std::collections::{BTreeMap, BTreeSet};
 
-const GLOBAL_INVARIANT_FAILS_MESSAGE: &str = "global memory invariant does not hold";
+pub const GLOBAL_INVARIANT_FAILS_MESSAGE: &str = "global memory invariant does not hold";
 
 pub struct GlobalInvariantInstrumentationProcessorV2 {}
 
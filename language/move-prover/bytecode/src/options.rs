@@ -4,7 +4,7 @@
 use codespan_reporting::diagnostic::Severity;
 use move_model::model::{GlobalEnv, VerificationScope};
 use serde::{Deserialize, Serialize};
-use std::rc::Rc;
+use std::{collections::BTreeMap, rc::Rc};
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum AutoTraceLevel {
@@ -44,6 +44,15 @@ pub struct ProverOptions {
     pub stable_test_output: bool,
     /// Scope of what functions to verify.
     pub verify_scope: VerificationScope,
+    /// If set, only functions defined in this source file are verified. This narrows down
+    /// whatever is selected by `verify_scope`.
+    pub verify_file: Option<String>,
+    /// If set, only the given function and the functions it transitively calls are verified.
+    /// This narrows down whatever is selected by `verify_scope`.
+    pub verify_reachable_from: Option<String>,
+    /// Whether to only verify that struct invariants are established, skipping all other
+    /// verification conditions of the verified functions.
+    pub verify_struct_invariants_only: bool,
     /// [deprecated] Whether to emit global axiom that resources are well-formed.
     pub resource_wellformed_axiom: bool,
     /// Whether to assume wellformedness when elements are read from memory, instead of on
@@ -77,6 +86,19 @@ pub struct ProverOptions {
     pub unconditional_abort_as_inconsistency: bool,
     /// Whether to run the transformation passes for concrete interpretation (instead of proving)
     pub for_interpretation: bool,
+    /// Per-function timeouts (in seconds), keyed by `Module::function`, which override the
+    /// default timeout for the verification of those functions.
+    pub timeout_overrides: BTreeMap<String, usize>,
+    /// Whether to warn about variables bound in specs which are never used.
+    pub warn_unused_spec_vars: bool,
+    /// Whether to warn about private functions which can't be reached from any entry point.
+    pub warn_unreachable_functions: bool,
+    /// Whether to fail before verification if any warnings have been reported, e.g. by the
+    /// spec lints above.
+    pub deny_warnings: bool,
+    /// Functions in this scope must have a specification. Only the presence of spec conditions
+    /// is checked, not whether they verify.
+    pub require_specs: VerificationScope,
 }
 
 impl Default for ProverOptions {
@@ -88,6 +110,9 @@ impl Default for ProverOptions {
             omit_model_debug: false,
             stable_test_output: false,
             verify_scope: VerificationScope::All,
+            verify_file: None,
+            verify_reachable_from: None,
+            verify_struct_invariants_only: false,
             resource_wellformed_axiom: false,
             assume_wellformed_on_access: false,
             mutation: false,
@@ -103,6 +128,11 @@ impl Default for ProverOptions {
             check_inconsistency: false,
             unconditional_abort_as_inconsistency: false,
             for_interpretation: false,
+            timeout_overrides: BTreeMap::new(),
+            warn_unused_spec_vars: false,
+            warn_unreachable_functions: false,
+            deny_warnings: false,
+            require_specs: VerificationScope::None,
         }
     }
 }
@@ -4,6 +4,7 @@
 use crate::{
     borrow_analysis::BorrowAnalysisProcessor,
     clean_and_optimize::CleanAndOptimizeProcessor,
+    data_invariant_filter::DataInvariantFilterProcessor,
     data_invariant_instrumentation::DataInvariantInstrumentationProcessor,
     debug_instrumentation::DebugInstrumenter,
     eliminate_imm_refs::EliminateImmRefsProcessor,
@@ -43,6 +44,10 @@ pub fn default_pipeline_with_options(options: &ProverOptions) -> FunctionTargetP
         DataInvariantInstrumentationProcessor::new(),
         GlobalInvariantInstrumentationProcessorV2::new(),
     ];
+    if options.verify_struct_invariants_only {
+        // must come after all instrumentation which introduces assertions
+        processors.push(DataInvariantFilterProcessor::new());
+    }
     if options.mutation {
         processors.push(MutationTester::new()); // pass which may do nothing
     }
@@ -18,7 +18,10 @@ use move_model::{
         DISABLE_INVARIANTS_IN_BODY_PRAGMA, VERIFY_PRAGMA,
     },
 };
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fs,
+};
 
 /// The annotation for information about verification.
 #[derive(Clone, Default)]
@@ -74,6 +77,9 @@ pub struct InvariantAnalysisData {
     /// (2) in a function in non_inv_fun_set, it is the least set that includes all disabled_invs
     /// for calling functions.
     pub disabled_invs_for_fun: BTreeMap<QualifiedId<FunId>, BTreeSet<GlobalId>>,
+    /// If verification is restricted to the functions reachable from an entry function, the
+    /// entry function and all functions it transitively calls.
+    pub reachable_fun_ids: Option<BTreeSet<QualifiedId<FunId>>>,
 }
 
 /// Get all invariants from target modules
@@ -327,6 +333,38 @@ fn compute_dep_fun_ids(
     dep_fun_ids
 }
 
+/// Compute the functions of target modules matching `entry_name`, together with all functions
+/// they transitively call. Returns an empty set if no target function matches `entry_name`.
+fn compute_reachable_fun_ids(
+    global_env: &GlobalEnv,
+    entry_name: &str,
+) -> BTreeSet<QualifiedId<FunId>> {
+    let entries = global_env
+        .get_target_modules()
+        .iter()
+        .flat_map(|module_env| module_env.get_functions())
+        .filter(|fun_env| fun_env.matches_name(entry_name))
+        .map(|fun_env| fun_env.get_qualified_id())
+        .collect::<Vec<_>>();
+    compute_transitive_callees(global_env, entries)
+}
+
+/// Compute the given functions together with all functions they transitively call.
+pub fn compute_transitive_callees(
+    global_env: &GlobalEnv,
+    roots: impl IntoIterator<Item = QualifiedId<FunId>>,
+) -> BTreeSet<QualifiedId<FunId>> {
+    let mut worklist = roots.into_iter().collect::<Vec<_>>();
+    let mut reachable_fun_ids = BTreeSet::new();
+    while let Some(fun_id) = worklist.pop() {
+        if reachable_fun_ids.insert(fun_id) {
+            let fun_env = global_env.get_function(fun_id);
+            worklist.extend(fun_env.get_called_functions());
+        }
+    }
+    reachable_fun_ids
+}
+
 /// Compute a map from each invariant to the set of functions that modify state
 /// appearing in the invariant. Return that, and a second value that is the union
 /// of functions over all invariants in the first map.
@@ -607,7 +645,14 @@ impl FunctionTargetProcessor for VerificationAnalysisProcessorV2 {
                     is_in_target_mod && fun_env.module_env.matches_name(module_name)
                 }
                 VerificationScope::None => false,
-            };
+            } && options
+                .verify_file
+                .as_ref()
+                .map_or(true, |file| is_defined_in_file(fun_env, file))
+                && inv_ana_data
+                    .reachable_fun_ids
+                    .as_ref()
+                    .map_or(true, |reachable| reachable.contains(&fun_id));
             if is_verified {
                 debug!("marking `{}` to be verified", fun_env.get_full_name_str());
                 mark_verified(fun_env, variant.clone(), targets);
@@ -655,6 +700,33 @@ impl FunctionTargetProcessor for VerificationAnalysisProcessorV2 {
             _ => {}
         }
 
+        // If we are verifying only one file, check that this file is indeed among the sources.
+        if let Some(file) = &options.verify_file {
+            if !global_env
+                .get_source_file_names()
+                .iter()
+                .any(|name| is_same_file(name, file))
+            {
+                global_env.error(
+                    &global_env.unknown_loc(),
+                    &format!("file target {} is not among the sources", file),
+                )
+            }
+        }
+
+        // If we are verifying only functions reachable from an entry function, compute them and
+        // check that the entry function indeed exists.
+        let reachable_fun_ids = options.verify_reachable_from.as_ref().map(|name| {
+            let reachable = compute_reachable_fun_ids(global_env, name);
+            if reachable.is_empty() {
+                global_env.error(
+                    &global_env.unknown_loc(),
+                    &format!("function target {} does not exist in target modules", name),
+                )
+            }
+            reachable
+        });
+
         let target_modules = global_env.get_target_modules();
         let target_fun_ids: BTreeSet<QualifiedId<FunId>> = target_modules
             .iter()
@@ -708,6 +780,7 @@ impl FunctionTargetProcessor for VerificationAnalysisProcessorV2 {
             funs_that_delegate_to_caller,
             friend_fun_ids,
             disabled_invs_for_fun,
+            reachable_fun_ids,
         };
 
         // Note: To print verbose debugging info, use
@@ -717,6 +790,25 @@ impl FunctionTargetProcessor for VerificationAnalysisProcessorV2 {
     }
 }
 
+/// Returns true if the function is defined in the given source file.
+fn is_defined_in_file(fun_env: &FunctionEnv<'_>, file: &str) -> bool {
+    fun_env
+        .module_env
+        .env
+        .get_file_and_location(&fun_env.get_loc())
+        .map_or(false, |(name, _)| is_same_file(&name, file))
+}
+
+/// Returns true if both paths denote the same file. The paths are compared as given first, so
+/// that files which are not on disk can still be matched.
+fn is_same_file(path1: &str, path2: &str) -> bool {
+    path1 == path2
+        || matches!(
+            (fs::canonicalize(path1), fs::canonicalize(path2)),
+            (Ok(p1), Ok(p2)) if p1 == p2
+        )
+}
+
 /// Mark this function as being verified. If it has a friend and is verified only in the
 /// friends context, mark the friend instead. This also marks all functions directly or
 /// indirectly called by this function as inlined if they are not opaque.
@@ -66,6 +66,21 @@ pub struct Options {
     pub experimental_pipeline: bool,
     /// Whether to use the old polymorphic boogie backend.
     pub boogie_poly: bool,
+    /// The path to write a JUnit XML report of the verification results to, if any.
+    pub junit_path: Option<String>,
+    /// The path to write a JSON report of the verification status of spec conditions to, if
+    /// any.
+    pub spec_coverage_path: Option<String>,
+    /// The path to write the time spent in each phase and function to, in the folded stack
+    /// format consumed by flamegraph tools, if any.
+    pub timing_folded_path: Option<String>,
+    /// Whether to fail with a distinct error if verification timed out, and no other
+    /// verification errors were found.
+    pub timeout_is_error: bool,
+    /// Whether to report the statistics of the SMT solver for each verified function.
+    pub smt_stats: bool,
+    /// The path to write the SMT solver statistics to as JSON, if any. Implies `smt_stats`.
+    pub smt_stats_json_path: Option<String>,
     /// BEGIN OF STRUCTURED OPTIONS
     /// Options for the model builder.
     pub model_builder: ModelBuilderOptions,
@@ -103,6 +118,12 @@ impl Default for Options {
             errmapgen: ErrmapOptions::default(),
             experimental_pipeline: false,
             boogie_poly: false,
+            junit_path: None,
+            spec_coverage_path: None,
+            timing_folded_path: None,
+            timeout_is_error: false,
+            smt_stats: false,
+            smt_stats_json_path: None,
         }
     }
 }
@@ -234,6 +255,17 @@ impl Options {
                     .validator(is_number)
                     .help("sets a random seed for the prover (default 0)")
             )
+            .arg(
+                Arg::with_name("solver-option")
+                    .long("solver-option")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1)
+                    .value_name("KEY=VALUE")
+                    .help("passes an option to the SMT solver, e.g. `smt.random_seed=2` \
+                    (can be repeated). Options are not checked by the prover; an invalid \
+                    option results in a solver error")
+            )
             .arg(
                 Arg::with_name("cores")
                     .long("cores")
@@ -254,6 +286,16 @@ impl Options {
                     .help("sets a timeout (in seconds) for each \
                              individual verification condition (default 40)")
             )
+            .arg(
+                Arg::with_name("timeout-override")
+                    .long("timeout-override")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1)
+                    .value_name("FUNCTION=SECONDS")
+                    .help("overrides the timeout (in seconds) for the verification of the given \
+                    function, specified as `Module::function` (can be repeated)")
+            )
             .arg(
                 Arg::with_name("ignore-pragma-opaque-when-possible")
                     .long("ignore-pragma-opaque-when-possible")
@@ -320,6 +362,72 @@ impl Options {
                         "for benchmarking: how many times to call the backend on the verification problem",
                     ),
             )
+            .arg(
+                Arg::with_name("junit")
+                    .long("junit")
+                    .takes_value(true)
+                    .value_name("PATH")
+                    .help("writes the verification result of each function as a JUnit XML \
+                    report to the given file")
+            )
+            .arg(
+                Arg::with_name("spec-coverage")
+                    .long("spec-coverage")
+                    .takes_value(true)
+                    .value_name("PATH")
+                    .help("writes a JSON report of the verification status of each `aborts_if` \
+                    and `ensures` condition to the given file")
+            )
+            .arg(
+                Arg::with_name("timing-folded")
+                    .long("timing-folded")
+                    .takes_value(true)
+                    .value_name("PATH")
+                    .help("writes the time spent in each phase, bytecode processor and boogie \
+                    procedure to the given file, as folded stacks for flamegraph tools")
+            )
+            .arg(
+                Arg::with_name("check-determinism")
+                    .long("check-determinism")
+                    .help("calls the backend repeatedly (at least twice, or `--bench-repeat` times) \
+                    and reports an error for each function whose verification result differs \
+                    between runs")
+            )
+            .arg(
+                Arg::with_name("timeout-is-error")
+                    .long("timeout-is-error")
+                    .help("fails with a distinct exit code (2) if verification of some function \
+                    timed out, and no other verification errors were found")
+            )
+            .arg(
+                Arg::with_name("smt-stats")
+                    .long("smt-stats")
+                    .help("reports the quantifier instantiations, conflicts and decisions of the \
+                    SMT solver for each verified function. The numbers come from a re-run of \
+                    each function's SMT problem with z3 after verification, not from the \
+                    verification run itself, which roughly doubles the solving time. Not \
+                    supported with cvc4")
+            )
+            .arg(
+                Arg::with_name("smt-stats-json")
+                    .long("smt-stats-json")
+                    .takes_value(true)
+                    .value_name("PATH")
+                    .help("like `--smt-stats`, and also writes the statistics as JSON to the \
+                    given file")
+            )
+            .arg(
+                Arg::with_name("report-invariants-separately")
+                    .long("report-invariants-separately")
+                    .help("reports failures of global invariants grouped by invariant, \
+                    after and apart from the other verification errors")
+            )
+            .arg(
+                Arg::with_name("sort-diagnostics")
+                    .long("sort-diagnostics")
+                    .help("reports verification errors sorted by location, so that the output \
+                    is deterministic. This is implied if more than one instance is run")
+            )
             .arg(
                 Arg::with_name("mutation")
                     .long("mutation")
@@ -453,6 +561,34 @@ impl Options {
                     .help("treat functions that do not return (i.e., abort unconditionally) \
                     as inconsistency violations")
             )
+            .arg(
+                Arg::with_name("warn-unused-spec-vars")
+                    .long("warn-unused-spec-vars")
+                    .help("emits warnings for variables bound in specs (by `let`, quantifiers, or \
+                    as spec function parameters) which are never used")
+            )
+            .arg(
+                Arg::with_name("warn-unreachable-functions")
+                    .long("warn-unreachable-functions")
+                    .help("emits warnings for private functions which are not transitively \
+                    called by any entry point. Entry points are `public`, `public(script)`, and \
+                    `public(friend)` functions, as well as script functions")
+            )
+            .arg(
+                Arg::with_name("deny-warnings")
+                    .long("deny-warnings")
+                    .help("exits with an error before verification if any warnings have been \
+                    reported, e.g. by --warn-unused-spec-vars or --warn-unreachable-functions")
+            )
+            .arg(
+                Arg::with_name("require-specs")
+                    .long("require-specs")
+                    .takes_value(true)
+                    .possible_values(&["public", "all"])
+                    .value_name("SCOPE")
+                    .help("emits an error for each function in the given scope of the target \
+                    modules which has no spec conditions")
+            )
             .arg(
                 Arg::with_name("verify-only")
                     .long("verify-only")
@@ -461,6 +597,29 @@ impl Options {
                     .help("only generate verification condition for one function. \
                     This overrides verification scope and can be overridden by the pragma verify=false")
             )
+            .arg(
+                Arg::with_name("verify-file")
+                    .long("verify-file")
+                    .takes_value(true)
+                    .value_name("PATH")
+                    .help("only generate verification conditions for functions defined in \
+                    the given source file. This narrows down the verification scope")
+            )
+            .arg(
+                Arg::with_name("verify-reachable-from")
+                    .long("verify-reachable-from")
+                    .takes_value(true)
+                    .value_name("FUNCTION_NAME")
+                    .help("only generate verification conditions for the given function and \
+                    the functions it transitively calls. This narrows down the verification scope")
+            )
+            .arg(
+                Arg::with_name("verify-struct-invariants-only")
+                    .long("verify-struct-invariants-only")
+                    .help("only verify that struct invariants hold wherever structs are packed \
+                    or modified, skipping all other specifications. Errors are sorted by \
+                    location, which groups them by struct")
+            )
             .arg(
                 Arg::with_name("z3-trace")
                     .long("z3-trace")
@@ -568,6 +727,38 @@ impl Options {
             options.backend.bench_repeat =
                 matches.value_of("bench-repeat").unwrap().parse::<usize>()?;
         }
+        if matches.is_present("junit") {
+            options.junit_path = Some(matches.value_of("junit").unwrap().to_string());
+        }
+        if matches.is_present("spec-coverage") {
+            options.spec_coverage_path =
+                Some(matches.value_of("spec-coverage").unwrap().to_string());
+        }
+        if matches.is_present("timing-folded") {
+            options.timing_folded_path =
+                Some(matches.value_of("timing-folded").unwrap().to_string());
+            // Needed to obtain the time boogie spends on each procedure.
+            options.backend.boogie_flags.push("-trace".to_string());
+        }
+        if matches.is_present("check-determinism") {
+            options.backend.check_determinism = true;
+        }
+        if matches.is_present("timeout-is-error") {
+            options.timeout_is_error = true;
+        }
+        if matches.is_present("smt-stats-json") {
+            options.smt_stats_json_path =
+                Some(matches.value_of("smt-stats-json").unwrap().to_string());
+        }
+        if matches.is_present("smt-stats") || options.smt_stats_json_path.is_some() {
+            options.smt_stats = true;
+        }
+        if matches.is_present("report-invariants-separately") {
+            options.backend.report_invariants_separately = true;
+        }
+        if matches.is_present("sort-diagnostics") {
+            options.backend.sort_diagnostics = true;
+        }
         if matches.is_present("ignore-pragma-opaque-when-possible") {
             options.model_builder.ignore_pragma_opaque_when_possible = true;
         }
@@ -632,6 +823,36 @@ impl Options {
         if matches.is_present("timeout") {
             options.backend.vc_timeout = matches.value_of("timeout").unwrap().parse::<usize>()?;
         }
+        if matches.is_present("timeout-override") {
+            for value in get_vec("timeout-override") {
+                let (fun_name, timeout) = value.split_once('=').ok_or_else(|| {
+                    anyhow!(
+                        "invalid timeout override `{}`, expected `FUNCTION=SECONDS`",
+                        value
+                    )
+                })?;
+                options
+                    .prover
+                    .timeout_overrides
+                    .insert(fun_name.to_string(), timeout.parse::<usize>()?);
+            }
+        }
+        if matches.is_present("solver-option") {
+            for value in get_vec("solver-option") {
+                let (key, val) = value
+                    .split_once('=')
+                    .filter(|(key, val)| {
+                        !key.is_empty() && !val.is_empty() && !value.contains(char::is_whitespace)
+                    })
+                    .ok_or_else(|| {
+                        anyhow!("invalid solver option `{}`, expected `KEY=VALUE`", value)
+                    })?;
+                options
+                    .backend
+                    .solver_options
+                    .insert(key.to_string(), val.to_string());
+            }
+        }
         if matches.is_present("cores") {
             options.backend.proc_cores = matches.value_of("cores").unwrap().parse::<usize>()?;
         }
@@ -663,12 +884,46 @@ impl Options {
         if matches.is_present("unconditional-abort-as-inconsistency") {
             options.prover.unconditional_abort_as_inconsistency = true;
         }
+        if matches.is_present("warn-unused-spec-vars") {
+            options.prover.warn_unused_spec_vars = true;
+        }
+        if matches.is_present("warn-unreachable-functions") {
+            options.prover.warn_unreachable_functions = true;
+        }
+        if matches.is_present("deny-warnings") {
+            options.prover.deny_warnings = true;
+        }
+        if matches.is_present("require-specs") {
+            options.prover.require_specs = match matches.value_of("require-specs").unwrap() {
+                "public" => VerificationScope::Public,
+                "all" => VerificationScope::All,
+                _ => unreachable!("should not happen"),
+            }
+        }
 
         if matches.is_present("verify-only") {
             options.prover.verify_scope =
                 VerificationScope::Only(matches.value_of("verify-only").unwrap().to_string());
         }
 
+        if matches.is_present("verify-file") {
+            options.prover.verify_file = Some(matches.value_of("verify-file").unwrap().to_string());
+        }
+
+        if matches.is_present("verify-reachable-from") {
+            options.prover.verify_reachable_from = Some(
+                matches
+                    .value_of("verify-reachable-from")
+                    .unwrap()
+                    .to_string(),
+            );
+        }
+
+        if matches.is_present("verify-struct-invariants-only") {
+            options.prover.verify_struct_invariants_only = true;
+            options.backend.sort_diagnostics = true;
+        }
+
         if matches.is_present("z3-trace") {
             let mut fun_name = matches.value_of("z3-trace").unwrap();
             options.prover.verify_scope = VerificationScope::Only(fun_name.to_string());
@@ -678,6 +933,12 @@ impl Options {
             options.backend.z3_trace_file = Some(format!("{}.z3log", fun_name));
         }
 
+        if options.smt_stats && options.backend.use_cvc4 {
+            return Err(anyhow!(
+                "SMT statistics are only supported with z3, not with cvc4"
+            ));
+        }
+
         options.backend.derive_options();
 
         if matches.is_present("print-config") {
@@ -0,0 +1,102 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation of a JUnit XML report of the verification results, for consumption by CI systems.
+//! Each function of a target module is represented as a test case, which fails if verification
+//! errors have been reported for the function, and is skipped if it is not in verification scope.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use boogie_backend::boogie_wrapper::BoogieError;
+use bytecode::function_target_pipeline::FunctionTargetsHolder;
+use move_model::model::{FunId, GlobalEnv, QualifiedId};
+
+/// Writes a JUnit XML report for the functions in `targets` to `path`.
+pub fn write_junit_report(
+    env: &GlobalEnv,
+    targets: &FunctionTargetsHolder,
+    boogie_errors: &[BoogieError],
+    path: &str,
+) -> anyhow::Result<()> {
+    std::fs::write(path, junit_report(env, targets, boogie_errors))?;
+    Ok(())
+}
+
+/// Creates a JUnit XML report for the functions in `targets`, based on the errors boogie
+/// reported.
+pub fn junit_report(
+    env: &GlobalEnv,
+    targets: &FunctionTargetsHolder,
+    boogie_errors: &[BoogieError],
+) -> String {
+    // Associate errors with the functions being verified when they were found. Errors without
+    // an execution trace, like timeouts, are associated with the function they are located in.
+    let mut errors: BTreeMap<QualifiedId<FunId>, Vec<String>> = BTreeMap::new();
+    for error in boogie_errors {
+        let fun_env = error
+            .verified_function(env)
+            .or_else(|| env.get_enclosing_function(&error.loc));
+        if let Some(fun_env) = fun_env {
+            errors
+                .entry(fun_env.get_qualified_id())
+                .or_default()
+                .push(error.message.clone());
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    for module_env in env.get_modules().filter(|m| m.is_target()) {
+        let module_name = module_env.get_full_name_str();
+        let mut cases = String::new();
+        let (mut tests, mut failures, mut skipped) = (0, 0, 0);
+        for fun_env in module_env.get_functions() {
+            tests += 1;
+            let fun_name = fun_env.get_name().display(env.symbol_pool()).to_string();
+            let verified = targets
+                .get_target_variants(&fun_env)
+                .iter()
+                .any(|variant| variant.is_verified());
+            let _ = write!(
+                cases,
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                escape(&module_name),
+                escape(&fun_name)
+            );
+            if !verified {
+                skipped += 1;
+                cases.push_str(">\n      <skipped/>\n    </testcase>\n");
+            } else if let Some(msgs) = errors.get(&fun_env.get_qualified_id()) {
+                failures += 1;
+                let _ = write!(
+                    cases,
+                    ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                    escape(&msgs[0]),
+                    escape(&msgs.join("\n"))
+                );
+            } else {
+                cases.push_str("/>\n");
+            }
+        }
+        let _ = write!(
+            out,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n{}  </testsuite>\n",
+            escape(&module_name),
+            tests,
+            failures,
+            skipped,
+            cases
+        );
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// Escapes a string for use in XML text and attribute values.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -4,28 +4,49 @@
 #![forbid(unsafe_code)]
 
 use crate::cli::Options;
+use crate::timing::FoldedTimings;
 use abigen::Abigen;
 use anyhow::anyhow;
 use boogie_backend::{
-    add_prelude, boogie_wrapper::BoogieWrapper, bytecode_translator::BoogieTranslator,
+    add_prelude,
+    boogie_wrapper::{BoogieErrorKind, BoogieOutput, BoogieWrapper},
+    bytecode_translator::BoogieTranslator,
 };
 use bytecode::{
     function_target_pipeline::{FunctionTargetPipeline, FunctionTargetsHolder},
     pipeline_factory,
     read_write_set_analysis::{self, ReadWriteSetProcessor},
+    verification_analysis_v2::compute_transitive_callees,
+};
+use codespan_reporting::{
+    diagnostic::Severity,
+    term::termcolor::{ColorChoice, StandardStream, WriteColor},
 };
-use codespan_reporting::term::termcolor::{ColorChoice, StandardStream, WriteColor};
 use docgen::Docgen;
 use errmapgen::ErrmapGen;
 #[allow(unused_imports)]
 use log::{debug, info, warn};
 use move_model::{
-    code_writer::CodeWriter, model::GlobalEnv, parse_addresses_from_options,
-    run_model_builder_with_options,
+    ast::{ConditionKind, Exp, Spec},
+    code_writer::CodeWriter,
+    model::{GlobalEnv, VerificationScope},
+    parse_addresses_from_options, run_model_builder_with_options,
+    symbol::Symbol,
+};
+use std::{
+    cell::RefCell,
+    collections::BTreeSet,
+    fmt, fs,
+    io::Write,
+    path::PathBuf,
+    time::{Duration, Instant},
 };
-use std::{fs, path::PathBuf, time::Instant};
 
 pub mod cli;
+pub mod junit;
+pub mod smt_stats;
+pub mod spec_coverage;
+pub mod timing;
 
 // =================================================================================================
 // Prover API
@@ -37,7 +58,7 @@ pub fn run_move_prover_errors_to_stderr(options: Options) -> anyhow::Result<()>
 
 pub fn run_move_prover<W: WriteColor>(
     error_writer: &mut W,
-    options: Options,
+    mut options: Options,
 ) -> anyhow::Result<()> {
     let now = Instant::now();
 
@@ -55,6 +76,25 @@ pub fn run_move_prover<W: WriteColor>(
         error_writer,
         "exiting with model building errors",
     )?;
+    if options.prover.warn_unused_spec_vars {
+        check_unused_spec_vars(&env);
+    }
+    if options.prover.warn_unreachable_functions {
+        check_unreachable_functions(&env);
+    }
+    if options.prover.require_specs != VerificationScope::None {
+        check_required_specs(&env, &options.prover.require_specs);
+        check_errors(
+            &env,
+            &options,
+            error_writer,
+            "exiting with missing specifications",
+        )?;
+    }
+    if options.prover.deny_warnings && env.has_warnings() {
+        env.report_diag(error_writer, options.prover.report_severity);
+        return Err(anyhow!("exiting with warnings"));
+    }
     env.report_diag(error_writer, options.prover.report_severity);
 
     // Add the prover options as an extension to the environment, so they can be accessed
@@ -86,10 +126,11 @@ pub fn run_move_prover<W: WriteColor>(
 
     // Check correct backend versions.
     options.backend.check_tool_versions()?;
+    info!("using {}", options.backend.solver_info());
 
     // Create and process bytecode
     let now = Instant::now();
-    let targets = create_and_process_bytecode(&options, &env);
+    let (targets, processor_durations) = create_and_process_bytecode_with_timings(&options, &env);
     let trafo_duration = now.elapsed();
     check_errors(
         &env,
@@ -109,11 +150,34 @@ pub fn run_move_prover<W: WriteColor>(
         "exiting with boogie generation errors",
     )?;
 
+    // Have boogie log the SMT problems if solver statistics are requested.
+    let smt_stats_dir = if options.smt_stats {
+        let dir = smt_stats::log_dir(&options.output_path);
+        smt_stats::prepare_log_dir(&dir)?;
+        options
+            .backend
+            .boogie_flags
+            .push(smt_stats::boogie_flag(&dir));
+        Some(dir)
+    } else {
+        None
+    };
+
     // Verify boogie code.
     let now = Instant::now();
-    verify_boogie(&env, &options, &targets, code_writer)?;
+    let boogie_output = verify_boogie(&env, &options, &targets, code_writer)?;
     let verify_duration = now.elapsed();
 
+    // Write JUnit report if requested.
+    if let Some(junit_path) = &options.junit_path {
+        junit::write_junit_report(&env, &targets, &boogie_output.errors, junit_path)?;
+    }
+
+    // Write spec coverage report if requested.
+    if let Some(spec_coverage_path) = &options.spec_coverage_path {
+        spec_coverage::write_spec_coverage_report(&env, &targets, spec_coverage_path)?;
+    }
+
     // Report durations.
     info!(
         "{:.3}s build, {:.3}s trafo, {:.3}s gen, {:.3}s verify",
@@ -122,6 +186,46 @@ pub fn run_move_prover<W: WriteColor>(
         gen_duration.as_secs_f64(),
         verify_duration.as_secs_f64()
     );
+
+    // Write folded timings if requested.
+    if let Some(timing_folded_path) = &options.timing_folded_path {
+        let mut timings = FoldedTimings::default();
+        timings.record(&["model_building"], build_duration);
+        timings.record_with_children(
+            "bytecode_transformation",
+            trafo_duration,
+            &processor_durations,
+        );
+        timings.record(&["boogie_generation"], gen_duration);
+        timings.record_with_children("solving", verify_duration, &boogie_output.procedure_times());
+        timings.write(timing_folded_path)?;
+    }
+
+    // Report SMT solver statistics if requested.
+    if let Some(dir) = &smt_stats_dir {
+        let stats = smt_stats::collect(&options.backend, dir)?;
+        write!(error_writer, "{}", smt_stats::format_table(&stats))?;
+        if let Some(json_path) = &options.smt_stats_json_path {
+            smt_stats::write_json(&stats, json_path)?;
+        }
+        if !options.backend.keep_artifacts {
+            fs::remove_dir_all(dir).unwrap_or_default();
+        }
+    }
+
+    // Fail distinctly if the only verification errors are timeouts.
+    if options.timeout_is_error {
+        let is_timeout = |kind| kind == BoogieErrorKind::Inconclusive;
+        let timeouts = boogie_output
+            .errors
+            .iter()
+            .filter(|e| is_timeout(e.kind))
+            .count();
+        if timeouts > 0 && boogie_output.errors.iter().all(|e| is_timeout(e.kind)) {
+            env.report_diag(error_writer, options.prover.report_severity);
+            return Err(VerificationTimeout { count: timeouts }.into());
+        }
+    }
     check_errors(
         &env,
         &options,
@@ -130,6 +234,22 @@ pub fn run_move_prover<W: WriteColor>(
     )
 }
 
+/// The error returned by `run_move_prover` with `--timeout-is-error`, if verification of some
+/// functions timed out but no other verification errors were found.
+#[derive(Debug)]
+pub struct VerificationTimeout {
+    /// The number of verification conditions which timed out.
+    pub count: usize,
+}
+
+impl fmt::Display for VerificationTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "exiting with {} verification timeout(s)", self.count)
+    }
+}
+
+impl std::error::Error for VerificationTimeout {}
+
 pub fn check_errors<W: WriteColor>(
     env: &GlobalEnv,
     options: &Options,
@@ -144,6 +264,124 @@ pub fn check_errors<W: WriteColor>(
     }
 }
 
+/// Emits an error for each function of a target module in `scope` which has no spec conditions,
+/// such as `aborts_if` or `ensures`. Pragmas alone do not count as a specification.
+pub fn check_required_specs(env: &GlobalEnv, scope: &VerificationScope) {
+    // Errors are reported in source order, rather than in the order of the function ids.
+    let mut missing = BTreeSet::new();
+    for module_env in env.get_modules().filter(|m| m.is_target()) {
+        for fun_env in module_env.get_functions() {
+            let is_required = match scope {
+                VerificationScope::Public => fun_env.is_exposed(),
+                VerificationScope::All => true,
+                VerificationScope::Only(name) => fun_env.matches_name(name),
+                VerificationScope::OnlyModule(name) => module_env.matches_name(name),
+                VerificationScope::None => false,
+            };
+            if is_required && !fun_env.get_spec().has_conditions() {
+                missing.insert((fun_env.get_loc(), fun_env.get_full_name_str()));
+            }
+        }
+    }
+    for (loc, name) in missing {
+        env.error(&loc, &format!("function `{}` has no specification", name));
+    }
+}
+
+/// Emits a warning for each private function of a target module which can't be reached from any
+/// entry point through the call graph. Entry points are all functions which can be called from
+/// outside of their module, i.e. `public`, `public(script)`, and `public(friend)` functions, as
+/// well as functions of scripts. Calls from specs are not considered.
+pub fn check_unreachable_functions(env: &GlobalEnv) {
+    let entry_points = env
+        .get_modules()
+        .flat_map(|module_env| module_env.get_functions())
+        .filter(|fun_env| fun_env.is_exposed())
+        .map(|fun_env| fun_env.get_qualified_id())
+        .collect::<Vec<_>>();
+    let reachable = compute_transitive_callees(env, entry_points);
+    // Warnings are reported in source order, rather than in the order of the function ids.
+    let mut warnings = BTreeSet::new();
+    for module_env in env.get_modules().filter(|m| m.is_target()) {
+        for fun_env in module_env.get_functions() {
+            if !reachable.contains(&fun_env.get_qualified_id()) {
+                warnings.insert((fun_env.get_loc(), fun_env.get_full_name_str()));
+            }
+        }
+    }
+    for (loc, name) in warnings {
+        env.diag(
+            Severity::Warning,
+            &loc,
+            &format!("function `{}` is not reachable from any entry point", name),
+        );
+    }
+}
+
+/// Emits a warning for each variable bound in a spec of a target module which is never used.
+/// This covers `let` conditions, variables bound inside of spec expressions, and parameters of
+/// spec functions. Variables whose name starts with `_` are exempt.
+pub fn check_unused_spec_vars(env: &GlobalEnv) {
+    let is_exempt = |sym: Symbol| env.symbol_pool().string(sym).starts_with('_');
+    let is_used_in = |sym: Symbol, exp: &Exp| exp.free_vars(env).iter().any(|(s, _)| *s == sym);
+    // Specs can be included multiple times via schemas, so we collect the warnings in a set.
+    let mut warnings = BTreeSet::new();
+    let mut check_spec = |spec: &Spec| {
+        for cond in &spec.conditions {
+            for exp in cond.all_exps() {
+                for decl in exp.unused_bindings(env) {
+                    if !is_exempt(decl.name) {
+                        warnings.insert((env.get_node_loc(decl.id), decl.name));
+                    }
+                }
+            }
+            if let ConditionKind::LetPre(sym) | ConditionKind::LetPost(sym) = &cond.kind {
+                let used = spec.conditions.iter().any(|other| {
+                    !std::ptr::eq(other, cond) && other.all_exps().any(|e| is_used_in(*sym, e))
+                });
+                if !used && !is_exempt(*sym) {
+                    warnings.insert((cond.loc.clone(), *sym));
+                }
+            }
+        }
+    };
+    for module_env in env.get_modules().filter(|m| m.is_target()) {
+        check_spec(module_env.get_spec());
+        for struct_env in module_env.get_structs() {
+            check_spec(struct_env.get_spec());
+        }
+        for fun_env in module_env.get_functions() {
+            let spec = fun_env.get_spec();
+            check_spec(spec);
+            for impl_spec in spec.on_impl.values() {
+                check_spec(impl_spec);
+            }
+        }
+        for (_, decl) in module_env.get_spec_funs() {
+            if decl.is_move_fun {
+                continue;
+            }
+            if let Some(body) = &decl.body {
+                for (param, _) in &decl.params {
+                    if !is_used_in(*param, body) && !is_exempt(*param) {
+                        warnings.insert((decl.loc.clone(), *param));
+                    }
+                }
+            }
+        }
+    }
+    for (loc, sym) in warnings {
+        env.diag(
+            Severity::Warning,
+            &loc,
+            &format!(
+                "spec variable `{}` is bound but never used",
+                sym.display(env.symbol_pool())
+            ),
+        );
+    }
+}
+
 pub fn generate_boogie(
     env: &GlobalEnv,
     options: &Options,
@@ -161,7 +399,7 @@ pub fn verify_boogie(
     options: &Options,
     targets: &FunctionTargetsHolder,
     writer: CodeWriter,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<BoogieOutput> {
     let output_existed = std::path::Path::new(&options.output_path).exists();
     debug!("writing boogie to `{}`", &options.output_path);
     writer.process_result(|result| fs::write(&options.output_path, result))?;
@@ -171,15 +409,24 @@ pub fn verify_boogie(
         writer: &writer,
         options: &options.backend,
     };
-    boogie.call_boogie_and_verify_output(&options.output_path)?;
+    let output = boogie.call_boogie_and_verify_output(&options.output_path)?;
     if !output_existed && !options.backend.keep_artifacts {
         std::fs::remove_file(&options.output_path).unwrap_or_default();
     }
-    Ok(())
+    Ok(output)
 }
 
 /// Create bytecode and process it.
 pub fn create_and_process_bytecode(options: &Options, env: &GlobalEnv) -> FunctionTargetsHolder {
+    create_and_process_bytecode_with_timings(options, env).0
+}
+
+/// Create bytecode and process it, also returning the time spent in each processor of the
+/// pipeline. No timings are collected if the bytecode is dumped, as dumping would distort them.
+pub fn create_and_process_bytecode_with_timings(
+    options: &Options,
+    env: &GlobalEnv,
+) -> (FunctionTargetsHolder, Vec<(String, Duration)>) {
     let mut targets = FunctionTargetsHolder::default();
 
     // Add function targets for all functions in the environment.
@@ -213,12 +460,23 @@ pub fn create_and_process_bytecode(options: &Options, env: &GlobalEnv) -> Functi
             .cloned()
             .unwrap_or_else(|| "bytecode".to_string())
             .replace(".move", "");
-        pipeline.run_with_dump(env, &mut targets, &dump_file, options.prover.dump_cfg)
+        pipeline.run_with_dump(env, &mut targets, &dump_file, options.prover.dump_cfg);
+        (targets, vec![])
     } else {
-        pipeline.run(env, &mut targets);
+        let durations = RefCell::new(vec![]);
+        let last = RefCell::new(Instant::now());
+        pipeline.run_with_hook(
+            env,
+            &mut targets,
+            |_| *last.borrow_mut() = Instant::now(),
+            |_, processor, _| {
+                let now = Instant::now();
+                let elapsed = now.duration_since(last.replace(now));
+                durations.borrow_mut().push((processor.name(), elapsed));
+            },
+        );
+        (targets, durations.into_inner())
     }
-
-    targets
 }
 
 // Tools using the Move prover top-level driver
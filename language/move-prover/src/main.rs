@@ -5,7 +5,7 @@
 
 use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
 
-use move_prover::{cli::Options, run_move_prover};
+use move_prover::{cli::Options, run_move_prover, VerificationTimeout};
 use std::env;
 
 fn main() {
@@ -16,6 +16,10 @@ fn main() {
             eprintln!("caused by: {}", s);
             c = s.source();
         }
+        // Timeouts get their own exit code, so they can be told apart from verification errors.
+        if e.is::<VerificationTimeout>() {
+            std::process::exit(2)
+        }
         std::process::exit(1)
     }
 }
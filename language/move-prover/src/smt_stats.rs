@@ -0,0 +1,161 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Collects the statistics the SMT solver reports for each verified function. Boogie does not
+//! forward solver statistics, so it is instructed to log the SMT problem of each procedure into
+//! a directory, and each of these problems is solved again by z3 with `-st`, which prints the
+//! statistics on exit.
+
+use boogie_backend::options::BoogieOptions;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, path::Path, process::Command};
+
+// A statistic as printed by z3 with `-st`, e.g. ` :quant-instantiations  1234`
+static STATISTIC: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r":(?P<key>[a-z0-9-]+)\s+(?P<value>[0-9]+)\b").unwrap());
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct SmtStats {
+    /// The boogie procedure the statistics were collected for
+    pub procedure: String,
+    pub quant_instantiations: u64,
+    pub conflicts: u64,
+    pub decisions: u64,
+}
+
+impl SmtStats {
+    /// Parses the statistics printed by z3 with `-st`. Statistics which are missing, e.g.
+    /// because the problem had no quantifiers, are zero.
+    pub fn parse(procedure: &str, output: &str) -> Self {
+        let values = STATISTIC
+            .captures_iter(output)
+            .filter_map(|cap| Some((cap["key"].to_string(), cap["value"].parse::<u64>().ok()?)))
+            .collect::<BTreeMap<_, _>>();
+        let get = |key: &str| values.get(key).copied().unwrap_or(0);
+        Self {
+            procedure: procedure.to_string(),
+            quant_instantiations: get("quant-instantiations"),
+            conflicts: get("conflicts"),
+            decisions: get("decisions"),
+        }
+    }
+}
+
+/// Returns the directory boogie logs the SMT problems into, for the boogie file `output_path`.
+pub fn log_dir(output_path: &str) -> String {
+    format!("{}.smt_stats", output_path)
+}
+
+/// Returns the boogie flag which logs the SMT problem of each procedure into `dir`.
+pub fn boogie_flag(dir: &str) -> String {
+    format!("-proverLog:{}/@PROC@.smt", dir)
+}
+
+/// Creates an empty `dir` for boogie to log into.
+pub fn prepare_log_dir(dir: &str) -> anyhow::Result<()> {
+    if Path::new(dir).exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    fs::create_dir_all(dir)?;
+    Ok(())
+}
+
+/// Solves each SMT problem logged into `dir` with z3 and collects the reported statistics,
+/// ordered by the number of quantifier instantiations, most expensive first.
+pub fn collect(options: &BoogieOptions, dir: &str) -> anyhow::Result<Vec<SmtStats>> {
+    let mut stats = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().map(|ext| ext != "smt").unwrap_or(true) {
+            continue;
+        }
+        let procedure = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let output = Command::new(&options.z3_exe)
+            .arg("-st")
+            .arg(format!("-T:{}", options.vc_timeout))
+            .arg(&path)
+            .output()?;
+        stats.push(SmtStats::parse(
+            &procedure,
+            &String::from_utf8_lossy(&output.stdout),
+        ));
+    }
+    stats.sort_by(|a, b| {
+        b.quant_instantiations
+            .cmp(&a.quant_instantiations)
+            .then_with(|| a.procedure.cmp(&b.procedure))
+    });
+    Ok(stats)
+}
+
+/// Renders `stats` as a table with one row per procedure.
+pub fn format_table(stats: &[SmtStats]) -> String {
+    let width = stats
+        .iter()
+        .map(|s| s.procedure.len())
+        .chain(std::iter::once("procedure".len()))
+        .max()
+        .unwrap_or_default();
+    let mut table = format!(
+        "{:width$}  {:>20}  {:>12}  {:>12}\n",
+        "procedure",
+        "quant-instantiations",
+        "conflicts",
+        "decisions",
+        width = width
+    );
+    for s in stats {
+        table.push_str(&format!(
+            "{:width$}  {:>20}  {:>12}  {:>12}\n",
+            s.procedure,
+            s.quant_instantiations,
+            s.conflicts,
+            s.decisions,
+            width = width
+        ));
+    }
+    table
+}
+
+/// Writes `stats` to `path` as a JSON array.
+pub fn write_json(stats: &[SmtStats], path: &str) -> anyhow::Result<()> {
+    fs::write(path, serde_json::to_string_pretty(stats)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_z3_statistics() {
+        let output = "unsat\n\
+            (:added-eqs                 27\n \
+            :conflicts                 3\n \
+            :decisions                 12\n \
+            :max-memory                19.63\n \
+            :quant-instantiations      1234)\n";
+        assert_eq!(
+            SmtStats::parse("$42_M_f$verify", output),
+            SmtStats {
+                procedure: "$42_M_f$verify".to_string(),
+                quant_instantiations: 1234,
+                conflicts: 3,
+                decisions: 12,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_missing_statistics() {
+        let stats = SmtStats::parse("p", "sat\n(:decisions 5\n :rlimit-count 100)");
+        assert_eq!(stats.decisions, 5);
+        assert_eq!(stats.quant_instantiations, 0);
+        assert_eq!(stats.conflicts, 0);
+    }
+}
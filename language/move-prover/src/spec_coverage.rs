@@ -0,0 +1,98 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation of a JSON report on the verification status of the `aborts_if` and `ensures`
+//! conditions of the target functions.
+//!
+//! The prover backend does not tell which conditions the solver actually needed, so the report
+//! can't tell whether a verified condition is dead, e.g. an `aborts_if` which never holds. A
+//! condition is
+//! - `not_verified` if its function is not in verification scope,
+//! - `failed` if a verification error has been reported within the condition,
+//! - `trivial` if it is the constant `true` (for `ensures`) or `false` (for `aborts_if`), and
+//!   thus cannot contribute anything to the spec,
+//! - `verified` otherwise, i.e. it was part of a successful proof, whether or not the proof
+//!   depended on it.
+
+use bytecode::function_target_pipeline::FunctionTargetsHolder;
+use codespan::LineOffset;
+use codespan_reporting::diagnostic::Severity;
+use move_model::{
+    ast::{ConditionKind, Exp, Value},
+    model::{GlobalEnv, Loc},
+};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct ConditionCoverage {
+    function: String,
+    kind: String,
+    loc: String,
+    status: &'static str,
+}
+
+/// Writes a spec coverage report for the functions in `targets` to `path`.
+pub fn write_spec_coverage_report(
+    env: &GlobalEnv,
+    targets: &FunctionTargetsHolder,
+    path: &str,
+) -> anyhow::Result<()> {
+    let report = spec_coverage(env, targets);
+    std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+    Ok(())
+}
+
+fn spec_coverage(env: &GlobalEnv, targets: &FunctionTargetsHolder) -> Vec<ConditionCoverage> {
+    let error_locs = env
+        .get_diag_locs(Severity::Error)
+        .into_iter()
+        .map(|(loc, _)| loc)
+        .collect::<Vec<_>>();
+    let mut report = vec![];
+    for module_env in env.get_modules().filter(|m| m.is_target()) {
+        for fun_env in module_env.get_functions() {
+            let verified = targets
+                .get_target_variants(&fun_env)
+                .iter()
+                .any(|variant| variant.is_verified());
+            for cond in &fun_env.get_spec().conditions {
+                let trivial_value = match cond.kind {
+                    ConditionKind::Ensures => true,
+                    ConditionKind::AbortsIf => false,
+                    _ => continue,
+                };
+                let status = if !verified {
+                    "not_verified"
+                } else if error_locs.iter().any(|loc| contains(&cond.loc, loc)) {
+                    "failed"
+                } else if matches!(&cond.exp, Exp::Value(_, Value::Bool(b)) if *b == trivial_value)
+                {
+                    "trivial"
+                } else {
+                    "verified"
+                };
+                report.push(ConditionCoverage {
+                    function: fun_env.get_full_name_str(),
+                    kind: cond.kind.to_string(),
+                    loc: display_loc(env, &cond.loc),
+                    status,
+                });
+            }
+        }
+    }
+    report
+}
+
+/// Returns true if `inner` lies within `outer`.
+fn contains(outer: &Loc, inner: &Loc) -> bool {
+    outer.file_id() == inner.file_id()
+        && outer.span().start() <= inner.span().start()
+        && inner.span().end() <= outer.span().end()
+}
+
+fn display_loc(env: &GlobalEnv, loc: &Loc) -> String {
+    match env.get_file_and_location(loc) {
+        Some((fname, pos)) => format!("{}:{}", fname, pos.line + LineOffset(1)),
+        None => "<unknown>".to_string(),
+    }
+}
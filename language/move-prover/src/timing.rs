@@ -0,0 +1,92 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Collects the time spent in the phases of the prover and writes it in the folded stack format
+//! consumed by flamegraph tools. Each line has the form `phase;function count_ms`, where the
+//! stack elements are separated by `;` and the count is the self time of the innermost element
+//! in milliseconds.
+
+use std::{fs, time::Duration};
+
+#[derive(Debug, Default)]
+pub struct FoldedTimings {
+    lines: Vec<(String, u128)>,
+}
+
+impl FoldedTimings {
+    /// Records `duration` as the self time of the innermost element of `stack`.
+    pub fn record(&mut self, stack: &[&str], duration: Duration) {
+        // `;` separates stack elements and spaces separate the count, so neither may appear
+        // in a frame.
+        let frames = stack
+            .iter()
+            .map(|frame| frame.replace(|c: char| c == ';' || c == ' ', "_"))
+            .collect::<Vec<_>>();
+        self.lines.push((frames.join(";"), duration.as_millis()));
+    }
+
+    /// Records a phase which took `duration` in total, of which `children` is the breakdown.
+    /// Time not covered by the children is attributed to the phase itself.
+    pub fn record_with_children(
+        &mut self,
+        phase: &str,
+        duration: Duration,
+        children: &[(String, Duration)],
+    ) {
+        let mut self_time = duration;
+        for (child, child_duration) in children {
+            self.record(&[phase, child], *child_duration);
+            self_time = self_time.saturating_sub(*child_duration);
+        }
+        self.record(&[phase], self_time);
+    }
+
+    /// Returns the recorded timings in the folded stack format, one stack per line.
+    pub fn folded(&self) -> String {
+        self.lines
+            .iter()
+            .map(|(stack, count)| format!("{} {}\n", stack, count))
+            .collect()
+    }
+
+    /// Writes the recorded timings to `path`, one stack per line.
+    pub fn write(&self, path: &str) -> anyhow::Result<()> {
+        fs::write(path, self.folded())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_escapes_separators() {
+        let mut timings = FoldedTimings::default();
+        timings.record(&["solving", "M::f; verify"], Duration::from_millis(12));
+        assert_eq!(timings.folded(), "solving;M::f__verify 12\n");
+    }
+
+    #[test]
+    fn record_with_children_attributes_rest_to_phase() {
+        let mut timings = FoldedTimings::default();
+        let children = vec![
+            ("f".to_string(), Duration::from_millis(30)),
+            ("g".to_string(), Duration::from_millis(50)),
+        ];
+        timings.record_with_children("solving", Duration::from_millis(100), &children);
+        assert_eq!(timings.folded(), "solving;f 30\nsolving;g 50\nsolving 20\n");
+    }
+
+    #[test]
+    fn record_with_children_exceeding_phase() {
+        // Children can run in parallel, so their total may exceed the phase duration
+        let mut timings = FoldedTimings::default();
+        let children = vec![
+            ("f".to_string(), Duration::from_millis(80)),
+            ("g".to_string(), Duration::from_millis(80)),
+        ];
+        timings.record_with_children("solving", Duration::from_millis(100), &children);
+        assert_eq!(timings.folded(), "solving;f 80\nsolving;g 80\nsolving 0\n");
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for the JUnit report. These run verification, and are skipped if the prover tools are
+//! not configured.
+
+use codespan_reporting::term::termcolor::Buffer;
+use move_command_line_common::env::read_env_var;
+use move_prover::{cli::Options, run_move_prover};
+use tempfile::TempDir;
+
+const DETACHED_SPEC_SOURCE: &str = r#"
+module 0x42::TestJUnit {
+    spec module {
+        pragma verify = true;
+    }
+
+    fun incorrect(x: u64): u64 {
+        x + 1
+    }
+
+    // The failing post-condition is located here, outside of the function body.
+    spec incorrect {
+        ensures result == x;
+    }
+
+    fun correct(x: u64): u64 {
+        x
+    }
+    spec correct {
+        ensures result == x;
+    }
+}
+"#;
+
+#[test]
+fn junit_attributes_failures_in_detached_spec_blocks() -> anyhow::Result<()> {
+    if read_env_var("BOOGIE_EXE").is_empty() || read_env_var("Z3_EXE").is_empty() {
+        eprintln!("Prover tools are not configured, skipping the JUnit report test");
+        return Ok(());
+    }
+    let temp_dir = TempDir::new()?;
+    let source = temp_dir.path().join("junit.move");
+    std::fs::write(&source, DETACHED_SPEC_SOURCE)?;
+    let junit = temp_dir.path().join("junit.xml");
+    let args = vec![
+        "mvp_test".to_string(),
+        format!("--output={}", temp_dir.path().join("junit.bpl").display()),
+        format!("--junit={}", junit.display()),
+        source.display().to_string(),
+    ];
+    let options = Options::create_from_args(&args)?;
+
+    let mut error_writer = Buffer::no_color();
+    assert!(run_move_prover(&mut error_writer, options).is_err());
+
+    let report = std::fs::read_to_string(&junit)?;
+    assert!(
+        report.contains("name=\"incorrect\">\n      <failure"),
+        "`incorrect` is not reported as failing:\n{}",
+        report
+    );
+    assert!(
+        report.contains("name=\"correct\"/>"),
+        "`correct` is not reported as passing:\n{}",
+        report
+    );
+    Ok(())
+}
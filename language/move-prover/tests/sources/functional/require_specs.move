@@ -0,0 +1,28 @@
+// no-boogie-test
+// flag: --require-specs=public
+module 0x42::TestRequireSpecs {
+
+    public fun with_spec(x: u64): u64 {
+        x
+    }
+    spec with_spec {
+        ensures result == x;
+    }
+
+    // Pragmas alone do not count as a specification
+    public fun pragma_only(x: u64): u64 {
+        x
+    }
+    spec pragma_only {
+        pragma verify = false;
+    }
+
+    public fun without_spec(x: u64): u64 {
+        x
+    }
+
+    // Private functions are not in the `public` scope
+    fun private_without_spec(x: u64): u64 {
+        x
+    }
+}
@@ -0,0 +1,22 @@
+// no-boogie-test
+// flag: --warn-unreachable-functions
+// flag: --deny-warnings
+module 0x42::TestUnreachableFunctions {
+
+    public fun call_helper(): u64 {
+        helper()
+    }
+
+    fun helper(): u64 {
+        1
+    }
+
+    // Only called by another unreachable function
+    fun dead_callee(): u64 {
+        2
+    }
+
+    fun dead(): u64 {
+        dead_callee()
+    }
+}
@@ -0,0 +1,20 @@
+// no-boogie-test
+// flag: --warn-unused-spec-vars
+// flag: --deny-warnings
+module 0x42::TestUnusedSpecVars {
+
+    fun inc(x: u64): u64 {
+        x + 1
+    }
+    spec inc {
+        let unused = x + 1;
+        let used = x + 1;
+        let _exempt = x + 2;
+        ensures result == used;
+    }
+
+    spec module {
+        fun ignores_second(a: num, b: num): num { a }
+        fun ignores_exempt(a: num, _b: num): num { a }
+    }
+}
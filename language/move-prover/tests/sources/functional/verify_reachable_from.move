@@ -0,0 +1,29 @@
+// flag: --verify-reachable-from=call_helper
+module 0x42::TestVerifyReachableFrom {
+
+    spec module {
+        pragma verify = true;
+    }
+
+    public fun call_helper(x: u64): u64 {
+        helper(x)
+    }
+    spec call_helper {
+        ensures result == x;
+    }
+
+    fun helper(x: u64): u64 {
+        x
+    }
+    spec helper {
+        ensures result == x;
+    }
+
+    // Not reachable from `call_helper`, so its incorrect spec is not verified
+    public fun unreachable(x: u64): u64 {
+        x
+    }
+    spec unreachable {
+        ensures result == x + 1;
+    }
+}
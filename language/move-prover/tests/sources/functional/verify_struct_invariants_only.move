@@ -0,0 +1,31 @@
+// flag: --verify-struct-invariants-only
+module 0x42::TestVerifyStructInvariantsOnly {
+
+    spec module {
+        pragma verify = true;
+    }
+
+    struct S has drop {
+        x: u64,
+    }
+    spec S {
+        invariant x > 0;
+    }
+
+    public fun new(x: u64): S {
+        if (x == 0) {
+            S { x: 1 }
+        } else {
+            S { x }
+        }
+    }
+
+    // The post-condition is incorrect, but only struct invariants are verified
+    public fun incorrect(x: u64): u64 {
+        x
+    }
+    spec incorrect {
+        ensures result == x + 1;
+        aborts_if true;
+    }
+}
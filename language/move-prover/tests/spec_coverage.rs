@@ -0,0 +1,95 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for the spec coverage report. These run verification, and are skipped if the prover
+//! tools are not configured.
+
+use codespan_reporting::term::termcolor::Buffer;
+use move_command_line_common::env::read_env_var;
+use move_prover::{cli::Options, run_move_prover};
+use tempfile::TempDir;
+
+const SPEC_COVERAGE_SOURCE: &str = r#"
+module 0x42::TestSpecCoverage {
+    spec module {
+        pragma verify = true;
+    }
+
+    fun dead(x: u64): u64 {
+        x
+    }
+    spec dead {
+        // A u64 never exceeds MAX_U64, so this condition never holds.
+        aborts_if x > MAX_U64;
+    }
+
+    fun live(x: u64): u64 {
+        x + 1
+    }
+    spec live {
+        aborts_if x + 1 > MAX_U64;
+    }
+
+    fun trivial(x: u64): u64 {
+        x
+    }
+    spec trivial {
+        aborts_if false;
+    }
+
+    fun unverified(x: u64): u64 {
+        x
+    }
+    spec unverified {
+        pragma verify = false;
+        aborts_if x > MAX_U64;
+    }
+}
+"#;
+
+#[test]
+fn spec_coverage_reports_status_of_aborts_if() -> anyhow::Result<()> {
+    if read_env_var("BOOGIE_EXE").is_empty() || read_env_var("Z3_EXE").is_empty() {
+        eprintln!("Prover tools are not configured, skipping the spec coverage test");
+        return Ok(());
+    }
+    let temp_dir = TempDir::new()?;
+    let source = temp_dir.path().join("spec_coverage.move");
+    std::fs::write(&source, SPEC_COVERAGE_SOURCE)?;
+    let report_path = temp_dir.path().join("spec_coverage.json");
+    let args = vec![
+        "mvp_test".to_string(),
+        format!(
+            "--output={}",
+            temp_dir.path().join("spec_coverage.bpl").display()
+        ),
+        format!("--spec-coverage={}", report_path.display()),
+        source.display().to_string(),
+    ];
+    let options = Options::create_from_args(&args)?;
+
+    let mut error_writer = Buffer::no_color();
+    run_move_prover(&mut error_writer, options)?;
+
+    let report: Vec<serde_json::Value> =
+        serde_json::from_str(&std::fs::read_to_string(&report_path)?)?;
+    let status_of = |function: &str| {
+        let entries = report
+            .iter()
+            .filter(|entry| {
+                entry["function"]
+                    .as_str()
+                    .unwrap()
+                    .ends_with(&format!("::{}", function))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 1, "unexpected entries for `{}`", function);
+        entries[0]["status"].as_str().unwrap().to_string()
+    };
+    // The report can't tell that the condition of `dead` never holds, it was merely verified.
+    assert_eq!(status_of("dead"), "verified");
+    assert_eq!(status_of("live"), "verified");
+    assert_eq!(status_of("trivial"), "trivial");
+    assert_eq!(status_of("unverified"), "not_verified");
+    Ok(())
+}
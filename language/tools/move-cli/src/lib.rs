@@ -72,6 +72,12 @@ pub struct Move {
     )]
     mode: ModeType,
 
+    /// Generate the interface files of the modules in storage into a subdirectory named after a
+    /// hash of these modules, instead of replacing the interface files of earlier states of
+    /// storage. This makes it safe to run several commands on the same storage concurrently.
+    #[structopt(long, global = true)]
+    separate_interfaces_by_hash: bool,
+
     /// Print additional diagnostics
     #[structopt(short = "v", global = true)]
     verbose: bool,
@@ -209,6 +215,14 @@ pub enum SandboxCommand {
         /// Path to a resource, events file, or module stored on disk.
         #[structopt(name = "file")]
         file: String,
+        /// Also print the raw BCS bytes of a resource, in hex. The bytes are printed before the
+        /// resource is deserialized, so they are shown even if deserialization fails.
+        #[structopt(long = "raw")]
+        raw: bool,
+        /// Only annotate nested structs and vectors of a resource up to this many levels below
+        /// its fields. Deeper values are elided, which keeps large resources readable.
+        #[structopt(long = "max-depth")]
+        max_depth: Option<usize>,
     },
     /// Delete all resources, events, and modules stored on disk under `storage`.
     /// Does *not* delete anything in `src`.
@@ -216,6 +230,10 @@ pub enum SandboxCommand {
     /// Run well-formedness checks on the `storage` and `build` directories.
     #[structopt(name = "doctor")]
     Doctor {},
+    /// Re-annotate all resources under `storage` using the current modules, and report those
+    /// which no longer annotate (e.g., after a module was upgraded with an incompatible layout).
+    #[structopt(name = "reannotate")]
+    Reannotate {},
     /// Typecheck and verify the scripts and/or modules under `src`.
     #[structopt(name = "link")]
     Link {
@@ -285,7 +303,11 @@ fn handle_experimental_commands(
             type_args,
             concretize,
         } => {
-            let state = mode.prepare_state(&move_args.build_dir, &move_args.storage_dir)?;
+            let state = mode.prepare_state(
+                &move_args.build_dir,
+                &move_args.storage_dir,
+                move_args.separate_interfaces_by_hash,
+            )?;
             experimental::commands::analyze_read_write_set(
                 &state,
                 module_file,
@@ -314,7 +336,11 @@ fn handle_sandbox_commands(
             source_files,
             no_republish,
         } => {
-            let state = mode.prepare_state(&move_args.build_dir, &move_args.storage_dir)?;
+            let state = mode.prepare_state(
+                &move_args.build_dir,
+                &move_args.storage_dir,
+                move_args.separate_interfaces_by_hash,
+            )?;
             base::commands::check(
                 &[state.interface_files_dir()?],
                 !*no_republish,
@@ -329,7 +355,11 @@ fn handle_sandbox_commands(
             ignore_breaking_changes,
             override_ordering,
         } => {
-            let state = mode.prepare_state(&move_args.build_dir, &move_args.storage_dir)?;
+            let state = mode.prepare_state(
+                &move_args.build_dir,
+                &move_args.storage_dir,
+                move_args.separate_interfaces_by_hash,
+            )?;
             sandbox::commands::publish(
                 natives,
                 &state,
@@ -350,7 +380,11 @@ fn handle_sandbox_commands(
             gas_budget,
             dry_run,
         } => {
-            let state = mode.prepare_state(&move_args.build_dir, &move_args.storage_dir)?;
+            let state = mode.prepare_state(
+                &move_args.build_dir,
+                &move_args.storage_dir,
+                move_args.separate_interfaces_by_hash,
+            )?;
             sandbox::commands::run(
                 natives,
                 error_descriptions,
@@ -383,9 +417,17 @@ fn handle_sandbox_commands(
             *use_temp_dir,
             *track_cov,
         ),
-        SandboxCommand::View { file } => {
-            let state = mode.prepare_state(&move_args.build_dir, &move_args.storage_dir)?;
-            sandbox::commands::view(&state, file)
+        SandboxCommand::View {
+            file,
+            raw,
+            max_depth,
+        } => {
+            let state = mode.prepare_state(
+                &move_args.build_dir,
+                &move_args.storage_dir,
+                move_args.separate_interfaces_by_hash,
+            )?;
+            sandbox::commands::view(&state, file, *raw, *max_depth)
         }
         SandboxCommand::Clean {} => {
             // delete storage
@@ -402,9 +444,21 @@ fn handle_sandbox_commands(
             Ok(())
         }
         SandboxCommand::Doctor {} => {
-            let state = mode.prepare_state(&move_args.build_dir, &move_args.storage_dir)?;
+            let state = mode.prepare_state(
+                &move_args.build_dir,
+                &move_args.storage_dir,
+                move_args.separate_interfaces_by_hash,
+            )?;
             sandbox::commands::doctor(&state)
         }
+        SandboxCommand::Reannotate {} => {
+            let state = mode.prepare_state(
+                &move_args.build_dir,
+                &move_args.storage_dir,
+                move_args.separate_interfaces_by_hash,
+            )?;
+            sandbox::commands::reannotate(&state)
+        }
     }
 }
 
@@ -424,7 +478,11 @@ pub fn run_cli(
             no_source_maps,
             check,
         } => {
-            let state = mode.prepare_state(&move_args.build_dir, &move_args.storage_dir)?;
+            let state = mode.prepare_state(
+                &move_args.build_dir,
+                &move_args.storage_dir,
+                move_args.separate_interfaces_by_hash,
+            )?;
             if *check {
                 base::commands::check(
                     &[state.interface_files_dir()?],
@@ -3,12 +3,14 @@
 
 pub mod doctor;
 pub mod publish;
+pub mod reannotate;
 pub mod run;
 pub mod test;
 pub mod view;
 
 pub use doctor::*;
 pub use publish::*;
+pub use reannotate::*;
 pub use run::*;
 pub use test::*;
 pub use view::*;
@@ -0,0 +1,47 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::sandbox::utils::on_disk_state_view::OnDiskStateView;
+
+use anyhow::{bail, Result};
+
+/// Re-annotate every resource in storage against the modules currently in storage. This is meant
+/// to be run after publishing a new version of a module (e.g., with
+/// `publish --ignore-breaking-changes`): a resource which no longer annotates indicates an
+/// incompatible change to the layout of its type. Prints a summary of the resources which
+/// annotate successfully and of those which fail, and returns an error if any of them fail.
+pub fn reannotate(state: &OnDiskStateView) -> Result<()> {
+    let mut ok = 0;
+    let mut failed = vec![];
+    for resource_path in state.resource_paths() {
+        if let Err(e) = state.view_resource(&resource_path) {
+            let (addr, tag) = OnDiskStateView::parse_resource_path(&resource_path)?;
+            failed.push((addr, tag, e));
+        } else {
+            ok += 1;
+        }
+    }
+
+    println!(
+        "Re-annotated {} resources: {} ok, {} failed",
+        ok + failed.len(),
+        ok,
+        failed.len()
+    );
+    for (addr, tag, e) in &failed {
+        println!(
+            "  {} under address 0x{}: {}",
+            tag,
+            addr.short_str_lossless(),
+            e
+        );
+    }
+
+    if !failed.is_empty() {
+        bail!(
+            "{} resources no longer annotate against the current modules",
+            failed.len()
+        )
+    }
+    Ok(())
+}
@@ -7,11 +7,26 @@ use crate::sandbox::utils::{
 
 use anyhow::{bail, Result};
 use std::path::Path;
-/// Print a module or resource stored in `file`
-pub fn view(state: &OnDiskStateView, file: &str) -> Result<()> {
+/// Print a module or resource stored in `file`. If `raw` is set, the BCS bytes of a resource are
+/// printed as well. If `max_depth` is set, values nested deeper in a resource are elided.
+pub fn view(
+    state: &OnDiskStateView,
+    file: &str,
+    raw: bool,
+    max_depth: Option<usize>,
+) -> Result<()> {
     let path = Path::new(&file);
     if state.is_resource_path(path) {
-        match state.view_resource(path)? {
+        if raw {
+            if let Some(bytes) = state.get_resource_bytes_at(path)? {
+                println!("Raw bytes: {}", hex::encode(bytes));
+            }
+        }
+        let resource = match max_depth {
+            Some(max_depth) => state.view_resource_shallow(path, max_depth)?,
+            None => state.view_resource(path)?,
+        };
+        match resource {
             Some(resource) => println!("{}", resource),
             None => println!("Resource not found."),
         }
@@ -101,15 +101,22 @@ impl Mode {
     }
 
     /// Prepare an OnDiskStateView that is ready to use. Library modules will be preloaded into the
-    /// storage if `load_libraries` is true.
+    /// storage if `load_libraries` is true. See `OnDiskStateView::set_separate_interfaces_by_hash`
+    /// for `separate_interfaces_by_hash`.
     ///
     /// NOTE: this is the only way to get a state view in Move CLI, and thus, this function needs
     /// to be run before every command that needs a state view, i.e., `check`, `publish`, `run`,
     /// `view`, and `doctor`.
-    pub fn prepare_state(&self, build_dir: &Path, storage_dir: &Path) -> Result<OnDiskStateView> {
+    pub fn prepare_state(
+        &self,
+        build_dir: &Path,
+        storage_dir: &Path,
+        separate_interfaces_by_hash: bool,
+    ) -> Result<OnDiskStateView> {
         let package_dir = build_dir.join(DEFAULT_PACKAGE_DIR);
         let named_address_values = self.prepare(&package_dir, false)?;
-        let state = OnDiskStateView::create(build_dir, storage_dir)?;
+        let mut state = OnDiskStateView::create(build_dir, storage_dir)?;
+        state.set_separate_interfaces_by_hash(separate_interfaces_by_hash);
 
         // preload the storage with library modules (if such modules do not exist yet)
         let lib_modules = self.compiled_modules(&package_dir)?;
@@ -19,12 +19,15 @@ use move_core_types::{
 };
 use move_lang::{shared::AddressBytes, MOVE_COMPILED_INTERFACES_DIR};
 use move_symbol_pool::Symbol;
+use rayon::prelude::*;
 use resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue, MoveValueAnnotator};
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     convert::{TryFrom, TryInto},
     fs,
+    io::Write,
     path::{Path, PathBuf},
 };
 
@@ -39,6 +42,9 @@ pub const EVENTS_DIR: &str = "events";
 
 pub type ModuleIdWithNamedAddress = (ModuleId, Option<Symbol>);
 
+/// The bytes of every resource in storage, keyed by address and type
+pub type ResourceSnapshot = BTreeMap<(AccountAddress, StructTag), Vec<u8>>;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct InterfaceFilesMetadata {
     named_address_mapping: BTreeMap<ModuleId, String>,
@@ -49,6 +55,9 @@ pub(crate) struct InterfaceFilesMetadata {
 pub struct OnDiskStateView {
     build_dir: PathBuf,
     storage_dir: PathBuf,
+    /// Whether interface files are generated into a subdirectory named after a hash of the
+    /// modules in storage, see `set_separate_interfaces_by_hash`.
+    separate_interfaces_by_hash: bool,
 }
 
 impl OnDiskStateView {
@@ -69,9 +78,21 @@ impl OnDiskStateView {
             // it is important to canonicalize the path here because `is_data_path()` relies on the
             // fact that storage_dir is canonicalized.
             storage_dir: storage_dir.canonicalize()?,
+            separate_interfaces_by_hash: false,
         })
     }
 
+    /// Controls where interface files for the modules in storage are generated. By default
+    /// (`false`), they are written directly into the interfaces directory and regenerated files
+    /// replace the existing ones, so the interfaces always mirror the current storage. If set to
+    /// `true`, they are written into a subdirectory named after a hash of the modules in storage,
+    /// and existing files in that subdirectory are never overwritten. This makes concurrent syncs
+    /// of the same storage safe, but interfaces generated for earlier states of storage are kept
+    /// next to the current ones and remain visible to the compiler.
+    pub fn set_separate_interfaces_by_hash(&mut self, separate_by_hash: bool) {
+        self.separate_interfaces_by_hash = separate_by_hash;
+    }
+
     pub fn interface_files_dir(&self) -> Result<String> {
         let path = self.build_dir.join(MOVE_COMPILED_INTERFACES_DIR);
         if !path.exists() {
@@ -224,6 +245,17 @@ impl OnDiskStateView {
         Self::get_bytes(&self.get_resource_path(addr, tag))
     }
 
+    /// Read the resource bytes stored on-disk at `resource_path`
+    pub fn get_resource_bytes_at(&self, resource_path: &Path) -> Result<Option<Vec<u8>>> {
+        if resource_path.is_dir() {
+            bail!(
+                "Bad resource path {:?}. Needed file, found directory",
+                resource_path
+            )
+        }
+        Self::get_bytes(resource_path)
+    }
+
     /// Read the resource bytes stored on-disk at `addr`/`tag`
     fn get_module_bytes(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>> {
         Self::get_bytes(&self.get_module_path(module_id))
@@ -265,6 +297,25 @@ impl OnDiskStateView {
     /// Returns a deserialized representation of the resource value stored at `resource_path`.
     /// Returns Err if the path does not hold a resource value or the resource cannot be deserialized
     pub fn view_resource(&self, resource_path: &Path) -> Result<Option<AnnotatedMoveStruct>> {
+        self.view_resource_with_depth(resource_path, None)
+    }
+
+    /// Like `view_resource`, but only annotates nested structs and vectors up to `max_depth`
+    /// levels below the fields of the resource, replacing deeper values by a placeholder. This
+    /// keeps the output of large resources readable.
+    pub fn view_resource_shallow(
+        &self,
+        resource_path: &Path,
+        max_depth: usize,
+    ) -> Result<Option<AnnotatedMoveStruct>> {
+        self.view_resource_with_depth(resource_path, Some(max_depth))
+    }
+
+    fn view_resource_with_depth(
+        &self,
+        resource_path: &Path,
+        max_depth: Option<usize>,
+    ) -> Result<Option<AnnotatedMoveStruct>> {
         if resource_path.is_dir() {
             bail!("Bad resource path {:?}. Needed file, found directory")
         }
@@ -280,7 +331,13 @@ impl OnDiskStateView {
                 };
                 match Self::get_bytes(resource_path)? {
                     Some(resource_data) => {
-                        Some(MoveValueAnnotator::new(self).view_resource(&id, &resource_data)?)
+                        let annotator = MoveValueAnnotator::new(self);
+                        Some(match max_depth {
+                            Some(max_depth) => {
+                                annotator.view_resource_shallow(&id, &resource_data, max_depth)?
+                            }
+                            None => annotator.view_resource(&id, &resource_data)?,
+                        })
                     }
                     None => None,
                 }
@@ -307,6 +364,53 @@ impl OnDiskStateView {
             .collect()
     }
 
+    /// View all events emitted under `addr`, keyed by the creation number of their event handle.
+    /// Returns an empty map if no events were ever stored under `addr`.
+    pub fn view_account_events(
+        &self,
+        addr: AccountAddress,
+    ) -> Result<BTreeMap<u64, Vec<AnnotatedMoveValue>>> {
+        let mut events_dir = self.get_addr_path(&addr);
+        events_dir.push(EVENTS_DIR);
+        let mut account_events = BTreeMap::new();
+        if !events_dir.is_dir() {
+            return Ok(account_events);
+        }
+        for entry in fs::read_dir(&events_dir)? {
+            let events_path = entry?.path();
+            if !self.is_event_path(&events_path) {
+                continue;
+            }
+            let creation_number = events_path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+                .ok_or_else(|| anyhow!("Unexpected event log path {:?}", events_path))?;
+            account_events.insert(creation_number, self.view_events(&events_path)?);
+        }
+        Ok(account_events)
+    }
+
+    /// Write every event in storage to `writer` as newline-delimited JSON, one object per event
+    /// holding its sequence number, type, and annotated value. Events are annotated and written
+    /// one event log at a time so the whole event history is never held in memory.
+    pub fn export_events_ndjson<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let annotator = MoveValueAnnotator::new(self);
+        for events_path in self.event_paths() {
+            for (_, sequence_number, event_type, event_data) in self.get_events(&events_path)? {
+                let value = annotator.view_value(&event_type, &event_data)?;
+                let entry = serde_json::json!({
+                    "sequence_number": sequence_number,
+                    "type": TypeID(event_type).to_string(),
+                    "value": value,
+                });
+                serde_json::to_writer(&mut *writer, &entry)?;
+                writeln!(writer)?;
+            }
+        }
+        Ok(())
+    }
+
     fn view_bytecode(path: &Path, is_module: bool) -> Result<Option<String>> {
         type Loc = u64;
         if path.is_dir() {
@@ -423,7 +527,7 @@ impl OnDiskStateView {
                     .unwrap(),
             ),
             &self.read_interface_files_metadata()?.named_address_mapping,
-            false,
+            self.separate_interfaces_by_hash,
         )?;
         Ok(())
     }
@@ -486,16 +590,159 @@ impl OnDiskStateView {
         self.iter_paths(move |p| self.is_event_path(p))
     }
 
+    /// Return the address and type of every resource in storage whose defining module is no
+    /// longer stored, e.g. because it was deleted. Such resources can't be annotated anymore.
+    pub fn find_orphaned_resources(&self) -> Result<Vec<(AccountAddress, StructTag)>> {
+        let mut orphans = vec![];
+        for resource_path in self.resource_paths() {
+            let (addr, tag) = Self::parse_resource_path(&resource_path)?;
+            if !self.has_module(&tag.module_id()) {
+                orphans.push((addr, tag));
+            }
+        }
+        orphans.sort();
+        Ok(orphans)
+    }
+
+    /// Recover the address and type of the resource stored at `resource_path`
+    pub(crate) fn parse_resource_path(resource_path: &Path) -> Result<(AccountAddress, StructTag)> {
+        let tag = match resource_path
+            .file_stem()
+            .map(|name| parser::parse_type_tag(&name.to_string_lossy()))
+            .transpose()?
+        {
+            Some(TypeTag::Struct(tag)) => tag,
+            _ => bail!("Bad resource path {:?}", resource_path),
+        };
+        let addr = match resource_path
+            .parent()
+            .and_then(|p| p.parent())
+            .and_then(|p| p.file_name())
+        {
+            Some(name) => AccountAddress::from_hex_literal(&name.to_string_lossy())?,
+            None => bail!("Bad resource path {:?}", resource_path),
+        };
+        Ok((addr, tag))
+    }
+
+    /// Capture the bytes of every resource in storage, so that a later call to
+    /// `changed_resources` or `check_state_diff` can diff the state against it.
+    pub fn snapshot_resources(&self) -> Result<ResourceSnapshot> {
+        let mut snapshot = BTreeMap::new();
+        for resource_path in self.resource_paths() {
+            let key = Self::parse_resource_path(&resource_path)?;
+            snapshot.insert(key, fs::read(&resource_path)?);
+        }
+        Ok(snapshot)
+    }
+
+    /// Return the address and type of every resource that was created, changed, or deleted
+    /// since `snapshot` was taken. This is a diff of the state, not a log of accesses: a resource
+    /// which was written with the bytes it already had is not reported.
+    pub fn changed_resources(
+        &self,
+        snapshot: &ResourceSnapshot,
+    ) -> Result<BTreeSet<(AccountAddress, StructTag)>> {
+        let current = self.snapshot_resources()?;
+        let mut changed = BTreeSet::new();
+        for (key, bytes) in &current {
+            if snapshot.get(key) != Some(bytes) {
+                changed.insert(key.clone());
+            }
+        }
+        for key in snapshot.keys() {
+            if !current.contains_key(key) {
+                changed.insert(key.clone());
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Fail if a resource outside of `expected` changed since `snapshot` was taken, as determined
+    /// by `changed_resources`. Resources in `expected` may change, but need not.
+    pub fn check_state_diff(
+        &self,
+        snapshot: &ResourceSnapshot,
+        expected: &BTreeSet<(AccountAddress, StructTag)>,
+    ) -> Result<()> {
+        let unexpected = self
+            .changed_resources(snapshot)?
+            .into_iter()
+            .filter(|key| !expected.contains(key))
+            .map(|(addr, tag)| format!("0x{}/{}", addr, StructID(tag).to_string()))
+            .collect::<Vec<_>>();
+        if !unexpected.is_empty() {
+            bail!(
+                "Resources changed outside of the expected state diff: {}",
+                unexpected.join(", ")
+            )
+        }
+        Ok(())
+    }
+
+    /// Compute a hash over the contents of all modules, resources, and events in storage. Paths
+    /// are visited in sorted order, so two views with the same logical state produce the same
+    /// hash regardless of the order in which that state was written.
+    pub fn state_hash(&self) -> Result<[u8; 32]> {
+        let mut paths = self
+            .iter_paths(move |p| {
+                self.is_module_path(p) || self.is_resource_path(p) || self.is_event_path(p)
+            })
+            .map(|path| {
+                let relative = path.strip_prefix(&self.storage_dir)?;
+                let components = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect::<Vec<_>>();
+                Ok((components.join("/"), path))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        paths.sort();
+
+        let mut hasher = Sha3_256::new();
+        hasher.update(b"OnDiskStateView");
+        for (name, path) in paths {
+            let bytes = fs::read(&path)?;
+            // length-prefix each entry so that (path, bytes) pairs cannot run into each other
+            hasher.update(&(name.len() as u64).to_le_bytes());
+            hasher.update(name.as_bytes());
+            hasher.update(&(bytes.len() as u64).to_le_bytes());
+            hasher.update(&bytes);
+        }
+        Ok(hasher.finalize().into())
+    }
+
     /// Build all modules in the self.storage_dir.
     /// Returns an Err if a module does not deserialize.
     pub fn get_all_modules(&self) -> Result<Vec<CompiledModule>> {
         self.module_paths()
             .map(|path| {
                 CompiledModule::deserialize(&Self::get_bytes(&path)?.unwrap())
-                    .map_err(|e| anyhow!("Failed to deserialized module: {:?}", e))
+                    .map_err(|e| anyhow!("Failed to deserialize module: {:?}", e))
             })
             .collect::<Result<Vec<CompiledModule>>>()
     }
+
+    /// Same as `get_all_modules`, but deserializes the modules on a pool of `num_threads`
+    /// threads (or rayon's default number of threads if `num_threads` is 0). The result is sorted
+    /// by module id so that it does not depend on the scheduling of the threads.
+    pub fn get_all_modules_parallel(&self, num_threads: usize) -> Result<Vec<CompiledModule>> {
+        let paths: Vec<PathBuf> = self.module_paths().collect();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()?;
+        let mut modules = pool.install(|| {
+            paths
+                .par_iter()
+                .map(|path| {
+                    CompiledModule::deserialize(&Self::get_bytes(path)?.unwrap())
+                        .map_err(|e| anyhow!("Failed to deserialize module: {:?}", e))
+                })
+                .collect::<Result<Vec<CompiledModule>>>()
+        })?;
+        modules.sort_by_key(|m| m.self_id());
+        Ok(modules)
+    }
 }
 
 impl ModuleResolver for OnDiskStateView {
@@ -1,9 +1,23 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use move_cli::sandbox::commands::test;
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{empty_module, CompiledModule},
+};
+use move_cli::sandbox::{
+    commands::{reannotate, test},
+    utils::OnDiskStateView,
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+};
 
-use std::path::PathBuf;
+use resource_viewer::AnnotatedMoveValue;
+use std::{collections::BTreeMap, path::PathBuf};
+use tempfile::TempDir;
 
 pub const CLI_BINARY_PATH: [&str; 6] = ["..", "..", "..", "target", "debug", "move"];
 pub const CLI_METATEST_PATH: [&str; 3] = ["tests", "metatests", "args.txt"];
@@ -35,3 +49,287 @@ fn run_metatest() {
     // temp workspace + without coverage
     assert!(test::run_all(&path_metatest, &path_cli_binary, true, false).is_ok());
 }
+
+/// Create an empty view in a fresh temporary directory, which is deleted when the returned
+/// `TempDir` is dropped.
+fn test_view() -> (TempDir, OnDiskStateView) {
+    let dir = tempfile::tempdir().unwrap();
+    let view =
+        OnDiskStateView::create(dir.path().join("build"), dir.path().join("storage")).unwrap();
+    (dir, view)
+}
+
+fn struct_tag(module: &str, name: &str) -> StructTag {
+    StructTag {
+        address: AccountAddress::from_hex_literal("0x1").unwrap(),
+        module: Identifier::new(module).unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params: vec![],
+    }
+}
+
+/// A module without any members, named `name`
+fn empty_module_named(name: &str) -> CompiledModule {
+    let mut module = empty_module();
+    module.identifiers[0] = Identifier::new(name).unwrap();
+    module
+}
+
+#[test]
+fn state_hash_is_independent_of_write_order() {
+    let addr1 = AccountAddress::from_hex_literal("0x1").unwrap();
+    let addr2 = AccountAddress::from_hex_literal("0x2").unwrap();
+    let resources = vec![
+        (addr1, struct_tag("M", "A"), vec![1, 2, 3]),
+        (addr2, struct_tag("M", "B"), vec![4, 5]),
+        (addr1, struct_tag("M", "C"), vec![6]),
+    ];
+
+    let (_dir1, view1) = test_view();
+    let (_dir2, view2) = test_view();
+    for (addr, tag, bytes) in resources.iter() {
+        view1.save_resource(*addr, tag.clone(), bytes).unwrap();
+    }
+    for (addr, tag, bytes) in resources.iter().rev() {
+        view2.save_resource(*addr, tag.clone(), bytes).unwrap();
+    }
+    assert_eq!(view1.state_hash().unwrap(), view2.state_hash().unwrap());
+
+    view2
+        .save_resource(addr1, struct_tag("M", "C"), &[7])
+        .unwrap();
+    assert_ne!(view1.state_hash().unwrap(), view2.state_hash().unwrap());
+}
+
+#[test]
+fn find_orphaned_resources_reports_resources_of_deleted_modules() {
+    let addr = AccountAddress::from_hex_literal("0x1").unwrap();
+
+    let (_dir, view) = test_view();
+    // only the existence of the module matters, so its bytes don't need to be a valid module
+    view.save_module(&struct_tag("M", "R").module_id(), &[0])
+        .unwrap();
+    view.save_module(&struct_tag("N", "S").module_id(), &[0])
+        .unwrap();
+    view.save_resource(addr, struct_tag("M", "R"), &[1])
+        .unwrap();
+    view.save_resource(addr, struct_tag("N", "S"), &[2])
+        .unwrap();
+    assert!(view.find_orphaned_resources().unwrap().is_empty());
+
+    view.delete_module(&struct_tag("N", "S").module_id())
+        .unwrap();
+    assert_eq!(
+        view.find_orphaned_resources().unwrap(),
+        vec![(addr, struct_tag("N", "S"))]
+    );
+}
+
+#[test]
+fn check_state_diff_rejects_unexpected_changes() {
+    let addr = AccountAddress::from_hex_literal("0x1").unwrap();
+    let (r, s, t) = (
+        struct_tag("M", "R"),
+        struct_tag("M", "S"),
+        struct_tag("M", "T"),
+    );
+
+    let (_dir, view) = test_view();
+    view.save_resource(addr, r.clone(), &[1]).unwrap();
+    view.save_resource(addr, s.clone(), &[2]).unwrap();
+    let snapshot = view.snapshot_resources().unwrap();
+
+    // rewriting a resource with the same bytes is not a change
+    view.save_resource(addr, r.clone(), &[1]).unwrap();
+    view.save_resource(addr, s.clone(), &[3]).unwrap();
+    view.save_resource(addr, t.clone(), &[4]).unwrap();
+    assert_eq!(
+        view.changed_resources(&snapshot).unwrap(),
+        vec![(addr, s.clone()), (addr, t.clone())]
+            .into_iter()
+            .collect()
+    );
+
+    let expected = vec![(addr, s.clone())].into_iter().collect();
+    assert!(view.check_state_diff(&snapshot, &expected).is_err());
+    let expected = vec![(addr, r), (addr, s), (addr, t)].into_iter().collect();
+    assert!(view.check_state_diff(&snapshot, &expected).is_ok());
+}
+
+/// The key of the event handle with `creation_number` under `addr`
+fn event_key(addr: AccountAddress, creation_number: u64) -> Vec<u8> {
+    let mut key = creation_number.to_le_bytes().to_vec();
+    key.extend(addr.to_vec());
+    key
+}
+
+#[test]
+fn view_account_events_groups_events_by_handle() {
+    let addr = AccountAddress::from_hex_literal("0xA").unwrap();
+    let other_addr = AccountAddress::from_hex_literal("0xB").unwrap();
+
+    let (_dir, view) = test_view();
+    assert!(view.view_account_events(addr).unwrap().is_empty());
+
+    let events = vec![
+        (event_key(addr, 0), 0, 5u64),
+        (event_key(addr, 1), 0, 6u64),
+        (event_key(addr, 0), 1, 7u64),
+        (event_key(other_addr, 0), 0, 8u64),
+    ];
+    for (key, sequence_number, value) in events {
+        view.save_event(
+            &key,
+            sequence_number,
+            TypeTag::U64,
+            bcs::to_bytes(&value).unwrap(),
+        )
+        .unwrap();
+    }
+
+    let account_events = view.view_account_events(addr).unwrap();
+    let values = account_events
+        .iter()
+        .map(|(creation_number, events)| {
+            let values = events
+                .iter()
+                .map(|event| match event {
+                    AnnotatedMoveValue::U64(value) => *value,
+                    _ => panic!("Unexpected event {:?}", event),
+                })
+                .collect::<Vec<_>>();
+            (*creation_number, values)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(values, vec![(0, vec![5, 7]), (1, vec![6])]);
+}
+
+#[test]
+fn export_events_ndjson_writes_one_json_object_per_event() {
+    let event_key = event_key(AccountAddress::from_hex_literal("0xA").unwrap(), 0);
+
+    let (_dir, view) = test_view();
+    for (sequence_number, value) in [5u64, 6u64].iter().enumerate() {
+        view.save_event(
+            &event_key,
+            sequence_number as u64,
+            TypeTag::U64,
+            bcs::to_bytes(value).unwrap(),
+        )
+        .unwrap();
+    }
+
+    let mut output = vec![];
+    view.export_events_ndjson(&mut output).unwrap();
+    let entries = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        vec![
+            serde_json::json!({"sequence_number": 0, "type": "u64", "value": 5}),
+            serde_json::json!({"sequence_number": 1, "type": "u64", "value": 6}),
+        ]
+    );
+}
+
+#[test]
+fn get_all_modules_parallel_matches_sequential() {
+    let (_dir, view) = test_view();
+    for i in 0..8 {
+        let module = empty_module_named(&format!("M{}", i));
+        let mut bytes = vec![];
+        module.serialize(&mut bytes).unwrap();
+        view.save_module(&module.self_id(), &bytes).unwrap();
+    }
+
+    let mut sequential = view.get_all_modules().unwrap();
+    sequential.sort_by_key(|m| m.self_id());
+    assert_eq!(sequential.len(), 8);
+    for num_threads in &[0, 1, 4] {
+        assert_eq!(
+            view.get_all_modules_parallel(*num_threads).unwrap(),
+            sequential
+        );
+    }
+}
+
+#[test]
+fn reannotate_fails_on_resources_which_no_longer_annotate() {
+    let addr = AccountAddress::from_hex_literal("0x1").unwrap();
+    let (_dir, view) = test_view();
+    reannotate(&view).unwrap();
+
+    // the defining module of this resource isn't stored, so it can't be annotated
+    view.save_resource(addr, struct_tag("M", "R"), &[1])
+        .unwrap();
+    let err = reannotate(&view).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("1 resources no longer annotate"),
+        "{}",
+        err
+    );
+}
+
+/// The paths of all interface files generated for `view`, relative to its interfaces directory
+fn interface_files(view: &OnDiskStateView) -> Vec<PathBuf> {
+    let interfaces_dir = PathBuf::from(view.interface_files_dir().unwrap());
+    let mut files = walkdir::WalkDir::new(&interfaces_dir)
+        .into_iter()
+        .map(|entry| entry.unwrap().into_path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "move"))
+        .map(|path| path.strip_prefix(&interfaces_dir).unwrap().to_path_buf())
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+#[test]
+fn interface_files_are_separated_by_hash_when_requested() {
+    let publish = |view: &OnDiskStateView, name: &str| {
+        let module = empty_module_named(name);
+        let mut bytes = vec![];
+        module.serialize(&mut bytes).unwrap();
+        view.save_modules(&[((module.self_id(), None), bytes)], BTreeMap::new())
+            .unwrap();
+    };
+    let addr_dir = PathBuf::from(format!("{}", empty_module().self_id().address()));
+
+    // by default, the interfaces mirror the modules in storage
+    let (_dir, view) = test_view();
+    publish(&view, "M");
+    publish(&view, "N");
+    assert_eq!(
+        interface_files(&view),
+        vec![addr_dir.join("M.move"), addr_dir.join("N.move")]
+    );
+
+    // separated by hash, the interfaces of each state of storage are kept in their own directory
+    let (_dir, mut view) = test_view();
+    view.set_separate_interfaces_by_hash(true);
+    publish(&view, "M");
+    let files = interface_files(&view);
+    assert_eq!(files.len(), 1);
+    let first_hash_dir = files[0].parent().unwrap().parent().unwrap().to_path_buf();
+    assert_eq!(files[0], first_hash_dir.join(&addr_dir).join("M.move"));
+
+    publish(&view, "N");
+    let files = interface_files(&view);
+    assert_eq!(files.len(), 3);
+    let second_hash_dir = files
+        .iter()
+        .map(|file| file.parent().unwrap().parent().unwrap())
+        .find(|hash_dir| *hash_dir != first_hash_dir)
+        .unwrap()
+        .to_path_buf();
+    let mut expected = vec![
+        first_hash_dir.join(&addr_dir).join("M.move"),
+        second_hash_dir.join(&addr_dir).join("M.move"),
+        second_hash_dir.join(&addr_dir).join("N.move"),
+    ];
+    expected.sort();
+    assert_eq!(files, expected);
+}
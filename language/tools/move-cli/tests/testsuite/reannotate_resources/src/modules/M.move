@@ -0,0 +1,9 @@
+address 0x2 {
+module M {
+    struct R has key { i: u64 }
+
+    public fun store(account: &signer, i: u64) {
+        move_to(account, R { i })
+    }
+}
+}
@@ -0,0 +1,6 @@
+script {
+    use 0x2::M;
+    fun main(account: signer) {
+        M::store(&account, 7);
+    }
+}
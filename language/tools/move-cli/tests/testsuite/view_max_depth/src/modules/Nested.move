@@ -0,0 +1,14 @@
+address 0x2 {
+module Nested {
+    use Std::Vector;
+
+    struct Leaf has store { v: u64 }
+    struct Inner has store { leaf: Leaf, leaves: vector<Leaf> }
+    struct Outer has key { n: u64, bytes: vector<u8>, inner: Inner }
+
+    public fun publish(account: &signer) {
+        let inner = Inner { leaf: Leaf { v: 2 }, leaves: Vector::singleton(Leaf { v: 3 }) };
+        move_to(account, Outer { n: 1, bytes: x"ab", inner })
+    }
+}
+}
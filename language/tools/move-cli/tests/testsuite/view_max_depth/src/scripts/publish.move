@@ -0,0 +1,7 @@
+script {
+    use 0x2::Nested;
+
+    fun publish(account: signer) {
+        Nested::publish(&account)
+    }
+}
@@ -48,6 +48,9 @@ pub enum AnnotatedMoveValue {
     Vector(TypeTag, Vec<AnnotatedMoveValue>),
     Bytes(Vec<u8>),
     Struct(AnnotatedMoveStruct),
+    /// A value of the given type which was not annotated because it is nested deeper than the
+    /// requested maximal depth.
+    Truncated(TypeTag),
 }
 
 impl AnnotatedMoveValue {
@@ -62,6 +65,7 @@ impl AnnotatedMoveValue {
             Vector(t, _) => t.clone(),
             Bytes(_) => TypeTag::Vector(Box::new(TypeTag::U8)),
             Struct(s) => TypeTag::Struct(s.type_.clone()),
+            Truncated(t) => t.clone(),
         }
     }
 }
@@ -82,12 +86,33 @@ impl<'a, T: MoveResolver> MoveValueAnnotator<'a, T> {
     }
 
     pub fn view_resource(&self, tag: &StructTag, blob: &[u8]) -> Result<AnnotatedMoveStruct> {
+        self.view_resource_with_depth(tag, blob, None)
+    }
+
+    /// Like `view_resource`, but only annotates structs and vectors up to `max_depth` levels
+    /// below the fields of the resource. Deeper values are replaced by
+    /// `AnnotatedMoveValue::Truncated`.
+    pub fn view_resource_shallow(
+        &self,
+        tag: &StructTag,
+        blob: &[u8],
+        max_depth: usize,
+    ) -> Result<AnnotatedMoveStruct> {
+        self.view_resource_with_depth(tag, blob, Some(max_depth))
+    }
+
+    fn view_resource_with_depth(
+        &self,
+        tag: &StructTag,
+        blob: &[u8],
+        max_depth: Option<usize>,
+    ) -> Result<AnnotatedMoveStruct> {
         let ty = self.cache.resolve_struct(tag)?;
         let struct_def = (&ty)
             .try_into()
             .map_err(|e: PartialVMError| e.finish(Location::Undefined).into_vm_status())?;
         let move_struct = MoveStruct::simple_deserialize(blob, &struct_def)?;
-        self.annotate_struct(&move_struct, &ty)
+        self.annotate_struct(&move_struct, &ty, max_depth)
     }
 
     pub fn view_value(&self, ty_tag: &TypeTag, blob: &[u8]) -> Result<AnnotatedMoveValue> {
@@ -96,13 +121,16 @@ impl<'a, T: MoveResolver> MoveValueAnnotator<'a, T> {
             .try_into()
             .map_err(|e: PartialVMError| e.finish(Location::Undefined).into_vm_status())?;
         let move_value = MoveValue::simple_deserialize(blob, &layout)?;
-        self.annotate_value(&move_value, &ty)
+        self.annotate_value(&move_value, &ty, None)
     }
 
+    /// Annotates `move_struct`. If `depth` is given, nested structs and vectors are annotated
+    /// only up to this many levels below the fields of the struct.
     fn annotate_struct(
         &self,
         move_struct: &MoveStruct,
         ty: &FatStructType,
+        depth: Option<usize>,
     ) -> Result<AnnotatedMoveStruct> {
         let struct_tag = ty
             .struct_tag()
@@ -110,7 +138,7 @@ impl<'a, T: MoveResolver> MoveValueAnnotator<'a, T> {
         let field_names = self.cache.get_field_names(ty)?;
         let mut annotated_fields = vec![];
         for (ty, v) in ty.layout.iter().zip(move_struct.fields().iter()) {
-            annotated_fields.push(self.annotate_value(v, ty)?);
+            annotated_fields.push(self.annotate_value(v, ty, depth)?);
         }
         Ok(AnnotatedMoveStruct {
             abilities: ty.abilities.0,
@@ -122,7 +150,30 @@ impl<'a, T: MoveResolver> MoveValueAnnotator<'a, T> {
         })
     }
 
-    fn annotate_value(&self, value: &MoveValue, ty: &FatType) -> Result<AnnotatedMoveValue> {
+    fn annotate_value(
+        &self,
+        value: &MoveValue,
+        ty: &FatType,
+        depth: Option<usize>,
+    ) -> Result<AnnotatedMoveValue> {
+        let is_nested = match (value, ty) {
+            (MoveValue::Vector(_), FatType::Vector(elem_ty)) => {
+                !matches!(elem_ty.as_ref(), FatType::U8)
+            }
+            (MoveValue::Struct(_), FatType::Struct(_)) => true,
+            _ => false,
+        };
+        if is_nested && depth == Some(0) {
+            return Ok(AnnotatedMoveValue::Truncated(
+                ty.type_tag()
+                    .map_err(|e| e.finish(Location::Undefined).into_vm_status())?,
+            ));
+        }
+        let depth = if is_nested {
+            depth.map(|d| d - 1)
+        } else {
+            depth
+        };
         Ok(match (value, ty) {
             (MoveValue::Bool(b), FatType::Bool) => AnnotatedMoveValue::Bool(*b),
             (MoveValue::U8(i), FatType::U8) => AnnotatedMoveValue::U8(*i),
@@ -141,12 +192,12 @@ impl<'a, T: MoveResolver> MoveValueAnnotator<'a, T> {
                 _ => AnnotatedMoveValue::Vector(
                     ty.type_tag().unwrap(),
                     a.iter()
-                        .map(|v| self.annotate_value(v, ty.as_ref()))
+                        .map(|v| self.annotate_value(v, ty.as_ref(), depth))
                         .collect::<Result<_>>()?,
                 ),
             },
             (MoveValue::Struct(s), FatType::Struct(ty)) => {
-                AnnotatedMoveValue::Struct(self.annotate_struct(s, ty.as_ref())?)
+                AnnotatedMoveValue::Struct(self.annotate_struct(s, ty.as_ref(), depth)?)
             }
             _ => {
                 return Err(anyhow!(
@@ -189,6 +240,7 @@ fn pretty_print_value(
         }
         AnnotatedMoveValue::Bytes(v) => write!(f, "{}", hex::encode(&v)),
         AnnotatedMoveValue::Struct(s) => pretty_print_struct(f, s, indent),
+        AnnotatedMoveValue::Truncated(t) => write!(f, "<{}: ...>", t),
     }
 }
 
@@ -272,6 +324,7 @@ impl serde::Serialize for AnnotatedMoveValue {
                 }
             }
             Struct(s) => s.serialize(serializer),
+            Truncated(t) => serializer.serialize_str(&format!("<{}: ...>", t)),
         }
     }
 }
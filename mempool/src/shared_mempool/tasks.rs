@@ -377,6 +377,7 @@ pub(crate) async fn process_consensus_request(mempool: &Mutex<CoreMempool>, req:
                 .map(|txn| (txn.sender, txn.sequence_number))
                 .collect();
             let mut txns;
+            let insertion_times;
             {
                 let mut mempool = mempool.lock();
                 // gc before pulling block as extra protection against txns that may expire in consensus
@@ -385,13 +386,22 @@ pub(crate) async fn process_consensus_request(mempool: &Mutex<CoreMempool>, req:
                 mempool.gc_by_expiration_time(curr_time);
                 let block_size = cmp::max(max_block_size, 1);
                 txns = mempool.get_block(block_size, exclude_transactions);
+                insertion_times = txns
+                    .iter()
+                    .map(|txn| {
+                        mempool
+                            .metrics_cache
+                            .get(&(txn.sender(), txn.sequence_number()))
+                            .copied()
+                    })
+                    .collect();
             }
             counters::mempool_service_transactions(counters::GET_BLOCK_LABEL, txns.len());
             txns.len();
             let pulled_block = txns.drain(..).map(SignedTransaction::into).collect();
 
             (
-                ConsensusResponse::GetBlockResponse(pulled_block),
+                ConsensusResponse::GetBlockResponse(pulled_block, insertion_times),
                 callback,
                 counters::GET_BLOCK_LABEL,
             )
@@ -26,7 +26,14 @@ use futures::{
     future::Future,
     task::{Context, Poll},
 };
-use std::{collections::HashMap, fmt, pin::Pin, sync::Arc, task::Waker, time::Instant};
+use std::{
+    collections::HashMap,
+    fmt,
+    pin::Pin,
+    sync::Arc,
+    task::Waker,
+    time::{Instant, SystemTime},
+};
 use storage_interface::DbReader;
 use subscription_service::ReconfigSubscription;
 use tokio::runtime::Handle;
@@ -158,8 +165,9 @@ impl fmt::Display for ConsensusRequest {
 
 /// Response sent from mempool to consensus.
 pub enum ConsensusResponse {
-    /// Block to submit to consensus
-    GetBlockResponse(Vec<SignedTransaction>),
+    /// Block to submit to consensus, along with the time each transaction was inserted into
+    /// mempool (if still known)
+    GetBlockResponse(Vec<SignedTransaction>, Vec<Option<SystemTime>>),
     CommitResponse(),
 }
 
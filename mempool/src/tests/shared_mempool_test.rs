@@ -4,7 +4,7 @@
 use crate::{
     mocks::MockSharedMempool,
     tests::common::{batch_add_signed_txn, TestTransaction},
-    CommitNotification, CommittedTransaction, ConsensusRequest,
+    CommitNotification, CommittedTransaction, ConsensusRequest, ConsensusResponse,
 };
 use futures::{
     channel::{mpsc, oneshot},
@@ -52,6 +52,48 @@ fn test_consensus_events_rejected_txns() {
     assert_eq!(timeline.get(0).unwrap(), &kept_txn);
 }
 
+#[test]
+fn test_consensus_get_block_insertion_times() {
+    let smp = MockSharedMempool::new(None);
+
+    let txns = vec![
+        TestTransaction::new(0, 0, 1).make_signed_transaction(),
+        TestTransaction::new(0, 1, 1).make_signed_transaction(),
+        TestTransaction::new(1, 0, 1).make_signed_transaction(),
+    ];
+    // Add txns to mempool
+    {
+        let mut pool = smp.mempool.lock();
+        assert!(batch_add_signed_txn(&mut pool, txns).is_ok());
+    }
+
+    let (callback, callback_rcv) = oneshot::channel();
+    let req = ConsensusRequest::GetBlockRequest(10, vec![], callback);
+    let mut consensus_sender = smp.consensus_sender.clone();
+    let response = block_on(async {
+        assert!(consensus_sender.send(req).await.is_ok());
+        callback_rcv.await.unwrap().unwrap()
+    });
+
+    let (block, insertion_times) = match response {
+        ConsensusResponse::GetBlockResponse(block, insertion_times) => (block, insertion_times),
+        _ => panic!("unexpected consensus response"),
+    };
+    assert_eq!(block.len(), 3);
+    assert_eq!(insertion_times.len(), block.len());
+
+    // Every pulled txn is reported with the time it entered mempool
+    let pool = smp.mempool.lock();
+    for (txn, insertion_time) in block.iter().zip(insertion_times.iter()) {
+        let expected = pool
+            .metrics_cache
+            .get(&(txn.sender(), txn.sequence_number()))
+            .copied();
+        assert!(expected.is_some());
+        assert_eq!(insertion_time, &expected);
+    }
+}
+
 #[test]
 fn test_state_sync_events_committed_txns() {
     let (mut state_sync_sender, state_sync_events) = mpsc::channel(1_024);
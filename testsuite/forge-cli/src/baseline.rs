@@ -0,0 +1,196 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Comparison of the metrics reported by a run against a baseline, so that performance
+//! regressions fail the run. A baseline file maps test names to their expected metrics, e.g.
+//!
+//! ```yaml
+//! performance::performance-benchmark:
+//!   avg_tps: 850
+//!   avg_latency: 1200
+//!   p99_latency: 3000
+//! ```
+
+use anyhow::{bail, Context, Result};
+use diem_logger::prelude::*;
+use forge::TestReport;
+use std::{collections::BTreeMap, fs, path::Path};
+
+type Baseline = BTreeMap<String, BTreeMap<String, f64>>;
+
+/// Which values of a metric are better.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Direction {
+    LowerIsBetter,
+    HigherIsBetter,
+}
+
+/// The direction of each metric which can be compared against a baseline. Metrics without a
+/// direction, e.g. mempool sizes, can't be in a baseline.
+fn direction(metric: &str) -> Option<Direction> {
+    match metric {
+        "avg_tps" | "submitted_txn" => Some(Direction::HigherIsBetter),
+        "avg_latency" | "p99_latency" | "expired_txn" => Some(Direction::LowerIsBetter),
+        // Phase durations reported by e.g. the compatibility test
+        _ if metric.ends_with("_duration_secs") => Some(Direction::LowerIsBetter),
+        _ => None,
+    }
+}
+
+/// Compares all metrics in `reports` which have a value in the baseline file at `path`, and
+/// fails if any of them is worse than its baseline by more than `tolerance_pct` percent.
+/// Metrics which are not in the baseline are ignored.
+pub fn check_regressions(path: &Path, tolerance_pct: f64, reports: &[TestReport]) -> Result<()> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+    let baseline: Baseline = serde_yaml::from_str(&contents)
+        .with_context(|| format!("Failed to parse baseline {}", path.display()))?;
+    info!("Comparison against baseline {}:", path.display());
+    compare_to_baseline(&baseline, tolerance_pct, reports)
+}
+
+fn compare_to_baseline(
+    baseline: &Baseline,
+    tolerance_pct: f64,
+    reports: &[TestReport],
+) -> Result<()> {
+    let mut regressions = Vec::new();
+    for metric in reports.iter().flat_map(|report| report.metrics()) {
+        let expected = match baseline
+            .get(&metric.test_name)
+            .and_then(|metrics| metrics.get(&metric.metric))
+        {
+            Some(expected) => *expected,
+            None => continue,
+        };
+        let direction = match direction(&metric.metric) {
+            Some(direction) => direction,
+            None => bail!(
+                "Metric {} of {} has no known direction, so it can't be in a baseline",
+                metric.metric,
+                metric.test_name
+            ),
+        };
+        let delta_pct = delta_pct(expected, metric.value);
+        let regressed = is_regression(direction, delta_pct, tolerance_pct);
+        info!(
+            "    {} {}: {:.1} (baseline {:.1}, {:+.1}%){}",
+            metric.test_name,
+            metric.metric,
+            metric.value,
+            expected,
+            delta_pct,
+            if regressed { " REGRESSED" } else { "" }
+        );
+        if regressed {
+            regressions.push(format!("{} {}", metric.test_name, metric.metric));
+        }
+    }
+
+    if !regressions.is_empty() {
+        bail!(
+            "Metrics regressed by more than {}% against the baseline: {:?}",
+            tolerance_pct,
+            regressions
+        );
+    }
+    Ok(())
+}
+
+/// The change from `expected` to `value`, in percent of `expected`.
+fn delta_pct(expected: f64, value: f64) -> f64 {
+    if expected == 0.0 {
+        0.0
+    } else {
+        (value - expected) / expected * 100.0
+    }
+}
+
+fn is_regression(direction: Direction, delta_pct: f64, tolerance_pct: f64) -> bool {
+    match direction {
+        Direction::LowerIsBetter => delta_pct > tolerance_pct,
+        Direction::HigherIsBetter => delta_pct < -tolerance_pct,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST: &str = "performance::performance-benchmark";
+
+    fn baseline(yaml: &str) -> Baseline {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn report(metrics: &[(&str, f64)]) -> TestReport {
+        let mut report = TestReport::new();
+        for (metric, value) in metrics {
+            report.report_metric(TEST, metric, *value);
+        }
+        report
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(direction("avg_tps"), Some(Direction::HigherIsBetter));
+        assert_eq!(direction("avg_latency"), Some(Direction::LowerIsBetter));
+        assert_eq!(direction("p99_latency"), Some(Direction::LowerIsBetter));
+        assert_eq!(direction("expired_txn"), Some(Direction::LowerIsBetter));
+        assert_eq!(
+            direction("first_batch_upgrade_duration_secs"),
+            Some(Direction::LowerIsBetter)
+        );
+        assert_eq!(direction("avg_mempool_size"), None);
+    }
+
+    #[test]
+    fn test_tolerance() {
+        assert_eq!(delta_pct(100.0, 110.0), 10.0);
+        assert_eq!(delta_pct(200.0, 150.0), -25.0);
+        assert_eq!(delta_pct(0.0, 5.0), 0.0);
+
+        // Exactly at the tolerance is not a regression
+        assert!(!is_regression(Direction::LowerIsBetter, 10.0, 10.0));
+        assert!(is_regression(Direction::LowerIsBetter, 10.1, 10.0));
+        assert!(!is_regression(Direction::LowerIsBetter, -50.0, 10.0));
+        assert!(!is_regression(Direction::HigherIsBetter, -10.0, 10.0));
+        assert!(is_regression(Direction::HigherIsBetter, -10.1, 10.0));
+        assert!(!is_regression(Direction::HigherIsBetter, 50.0, 10.0));
+    }
+
+    #[test]
+    fn test_compare_to_baseline() {
+        let baseline = baseline(&format!(
+            "{}:\n  avg_tps: 1000\n  avg_latency: 1000\n  setup_duration_secs: 100\n",
+            TEST
+        ));
+
+        // Within tolerance, or better than the baseline
+        let reports = [report(&[
+            ("avg_tps", 950.0),
+            ("avg_latency", 500.0),
+            ("setup_duration_secs", 105.0),
+            ("p99_latency", 1e9),
+        ])];
+        compare_to_baseline(&baseline, 10.0, &reports).unwrap();
+
+        // Each of TPS, latency and duration can regress
+        compare_to_baseline(&baseline, 10.0, &[report(&[("avg_tps", 850.0)])]).unwrap_err();
+        compare_to_baseline(&baseline, 10.0, &[report(&[("avg_latency", 1200.0)])]).unwrap_err();
+        compare_to_baseline(
+            &baseline,
+            10.0,
+            &[report(&[("setup_duration_secs", 150.0)])],
+        )
+        .unwrap_err();
+        // A larger tolerance accepts the same values
+        compare_to_baseline(&baseline, 25.0, &[report(&[("avg_latency", 1200.0)])]).unwrap();
+    }
+
+    #[test]
+    fn test_metric_without_direction_is_rejected() {
+        let baseline = baseline(&format!("{}:\n  avg_mempool_size: 10\n", TEST));
+        compare_to_baseline(&baseline, 10.0, &[report(&[("avg_mempool_size", 10.0)])]).unwrap_err();
+    }
+}
@@ -1,19 +1,29 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::bail;
+use diem_logger::{Level, Logger};
 use diem_sdk::{
-    client::{BlockingClient, MethodRequest},
-    move_types::account_address::AccountAddress,
-    transaction_builder::Currency,
+    client::BlockingClient, move_types::account_address::AccountAddress,
+    transaction_builder::Currency, types::chain_id::ChainId,
+};
+use forge::{forge_main, forge_main_with_reports, ForgeConfig, Options, Result, *};
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
-use forge::{forge_main, ForgeConfig, Options, Result, *};
-use std::{num::NonZeroUsize, time::Duration};
 use structopt::StructOpt;
 use testcases::{
-    compatibility_test::SimpleValidatorUpgrade, generate_traffic,
-    performance_test::PerformanceBenchmark,
+    clock_skew_test::ClockSkew, compatibility_test::SimpleValidatorUpgrade,
+    disk_pressure_test::DiskPressure, epoch_transition_test::EpochTransitionUnderLoad,
+    generate_traffic, generate_traffic_sampling_mempool, performance_test::PerformanceBenchmark,
+    validator_failover_test::ValidatorFailover,
 };
 
+mod baseline;
+mod suite_config;
+
 #[derive(StructOpt, Debug)]
 struct Args {
     #[structopt(
@@ -33,6 +43,29 @@ struct Args {
         default_value = "60"
     )]
     duration: u64,
+    #[structopt(
+        long,
+        help = "File in which emitted accounts are persisted and reused across runs"
+    )]
+    account_cache: Option<PathBuf>,
+    #[structopt(
+        long,
+        help = "If set, emitters stop early once the ratio of committed to submitted txns \
+                stays below this rate"
+    )]
+    circuit_breaker_min_success_rate: Option<f64>,
+    #[structopt(
+        long,
+        help = "Window in seconds over which the emitter success rate is computed",
+        default_value = "30"
+    )]
+    circuit_breaker_window: u64,
+    #[structopt(
+        long,
+        help = "Number of consecutive windows below the success rate after which emitters stop",
+        default_value = "3"
+    )]
+    circuit_breaker_sustained_windows: usize,
 
     #[structopt(flatten)]
     options: Options,
@@ -62,6 +95,43 @@ struct Args {
     base_image_tag: String,
     #[structopt(long, help = "Specify a test suite to run")]
     suite: Option<String>,
+    #[structopt(
+        long,
+        help = "File defining custom test suites, from which --suite is looked up"
+    )]
+    suite_config: Option<PathBuf>,
+    #[structopt(
+        long,
+        use_delimiter = true,
+        help = "Runs the performance benchmark once for each of the given validator counts, \
+                e.g. 10,30,50, and reports TPS and latency for each count"
+    )]
+    validator_counts: Vec<NonZeroUsize>,
+    #[structopt(
+        long,
+        help = "Baseline file of expected metrics per test, the run fails if any reported \
+                metric regresses against it"
+    )]
+    baseline: Option<PathBuf>,
+    #[structopt(
+        long,
+        help = "Percentage by which a metric may be worse than its --baseline value",
+        default_value = "10"
+    )]
+    regression_tolerance: f64,
+    #[structopt(
+        long,
+        help = "Number of k8s validators kept out of the validator set as standby, \
+                in addition to the nodes the tests ask for",
+        default_value = "0"
+    )]
+    num_standby_validators: usize,
+    #[structopt(
+        long,
+        help = "Directory to which performance tests export a CSV timeline of the blocks \
+                committed during the test"
+    )]
+    block_timeline_dir: Option<PathBuf>,
 }
 
 #[derive(StructOpt, Debug)]
@@ -110,9 +180,10 @@ struct Resize {
 }
 
 fn main() -> Result<()> {
-    let args = Args::from_args();
+    Logger::new().level(Level::Info).read_env().init();
+    let mut args = Args::from_args();
 
-    if let Some(ops_cmd) = args.ops_cmd {
+    if let Some(ops_cmd) = args.ops_cmd.take() {
         match ops_cmd {
             OperatorCommand::SetValidator(set_validator) => {
                 return set_validator_image_tag(
@@ -143,18 +214,63 @@ fn main() -> Result<()> {
         }
     }
 
+    if !args.validator_counts.is_empty() {
+        // A baseline holds the expected metrics of a single swarm size, so it can't be applied
+        // across the different validator counts of a scaling run
+        if args.baseline.is_some() {
+            bail!("--baseline can't be combined with --validator-counts");
+        }
+        let test_suite = apply_emitter_options(
+            ForgeConfig::default().with_network_tests(&[&PerformanceBenchmark::XUS]),
+            &args,
+        );
+        return if args.local_swarm {
+            run_scaling_benchmark(
+                test_suite,
+                LocalFactory::from_workspace()?,
+                &args.validator_counts,
+                &args.options,
+            )
+        } else {
+            run_scaling_benchmark(
+                test_suite,
+                K8sFactory::new(
+                    args.cluster_name,
+                    args.helm_repo,
+                    args.image_tag,
+                    args.base_image_tag,
+                )
+                .unwrap()
+                .with_num_standby_validators(args.num_standby_validators),
+                &args.validator_counts,
+                &args.options,
+            )
+        };
+    }
+
     if args.local_swarm {
-        forge_main(
-            local_test_suite(),
+        let test_suite = match (&args.suite_config, &args.suite) {
+            (Some(path), Some(suite)) => suite_config::load_test_suite(path, suite)?,
+            _ => local_test_suite(),
+        };
+        let test_suite = apply_emitter_options(test_suite, &args);
+        run_forge(
+            test_suite,
             LocalFactory::from_workspace()?,
             &args.options,
+            args.baseline.as_deref(),
+            args.regression_tolerance,
         )
     } else {
         let mut test_suite = k8s_test_suite();
         if let Some(suite) = args.suite.as_ref() {
-            test_suite = get_test_suite(suite);
+            test_suite = match &args.suite_config {
+                Some(path) => suite_config::load_test_suite(path, suite)?,
+                None => get_test_suite(suite),
+            };
         }
-        forge_main(
+        let test_suite = apply_emitter_options(test_suite, &args);
+        run_forge(
             test_suite,
             K8sFactory::new(
                 args.cluster_name,
@@ -162,16 +278,102 @@ fn main() -> Result<()> {
                 args.image_tag,
                 args.base_image_tag,
             )
-            .unwrap(),
+            .unwrap()
+            .with_num_standby_validators(args.num_standby_validators),
             &args.options,
+            args.baseline.as_deref(),
+            args.regression_tolerance,
         )
     }
 }
 
+/// Configures the transaction emitters of `config` from the emit_tx options in `args`, and where
+/// tests export their block timelines.
+fn apply_emitter_options(mut config: ForgeConfig<'static>, args: &Args) -> ForgeConfig<'static> {
+    if let Some(account_cache) = &args.account_cache {
+        config = config.with_emitter_account_cache(account_cache.clone());
+    }
+    if let Some(min_success_rate) = args.circuit_breaker_min_success_rate {
+        config = config.with_emitter_circuit_breaker(CircuitBreaker::new(
+            min_success_rate,
+            Duration::from_secs(args.circuit_breaker_window),
+            args.circuit_breaker_sustained_windows,
+        ));
+    }
+    if let Some(block_timeline_dir) = &args.block_timeline_dir {
+        config = config.with_block_timeline_dir(block_timeline_dir.clone());
+    }
+    config
+}
+
+/// Runs `test_suite`, and if a baseline is given, fails if any metric reported by the tests
+/// regressed against it by more than `regression_tolerance` percent.
+fn run_forge<F: Factory>(
+    test_suite: ForgeConfig<'_>,
+    factory: F,
+    options: &Options,
+    baseline: Option<&Path>,
+    regression_tolerance: f64,
+) -> Result<()> {
+    match baseline {
+        Some(path) => forge_main_with_reports(test_suite, factory, options, |reports| {
+            baseline::check_regressions(path, regression_tolerance, &reports)
+        }),
+        None => forge_main(test_suite, factory, options),
+    }
+}
+
+/// Runs `test_suite` once for each of `validator_counts`, launching a new swarm of that size each
+/// time, and prints the metrics reported by the tests for each validator count side by side.
+fn run_scaling_benchmark<F: Factory>(
+    test_suite: ForgeConfig<'_>,
+    factory: F,
+    validator_counts: &[NonZeroUsize],
+    options: &Options,
+) -> Result<()> {
+    if options.list {
+        return forge_main(test_suite, factory, options);
+    }
+
+    let mut results = Vec::new();
+    for validator_count in validator_counts {
+        println!("Running with {} validators", validator_count);
+        let config = test_suite
+            .clone()
+            .with_initial_validator_count(*validator_count);
+        forge_main_with_reports(config, &factory, options, |reports| {
+            results.push((validator_count, reports));
+            Ok(())
+        })?;
+    }
+
+    println!();
+    println!(
+        "{:>10} {:>40} {:>15} {:>12}",
+        "validators", "test", "metric", "value"
+    );
+    for (validator_count, reports) in &results {
+        for metric in reports.iter().flat_map(|report| report.metrics()) {
+            println!(
+                "{:>10} {:>40} {:>15} {:>12.1}",
+                validator_count, metric.test_name, metric.metric, metric.value
+            );
+        }
+    }
+    Ok(())
+}
+
 fn get_test_suite(suite_name: &str) -> ForgeConfig<'static> {
     match suite_name {
         "land_blocking_compat" => land_blocking_test_compat_suite(),
         "land_blocking" => land_blocking_test_suite(),
+        "disk_pressure" => disk_pressure_test_suite(),
+        "clock_skew" => clock_skew_test_suite(),
+        "validator_failover" => validator_failover_test_suite(),
+        "epoch_transition" => epoch_transition_test_suite(),
+        "smoke" => smoke_test_suite(),
+        "public_usage" => public_usage_test_suite(),
+        "admin" => admin_test_suite(),
         _ => k8s_test_suite(),
     }
 }
@@ -180,7 +382,7 @@ fn local_test_suite() -> ForgeConfig<'static> {
     ForgeConfig::default()
         .with_public_usage_tests(&[&FundAccount, &TransferCoins])
         .with_admin_tests(&[&GetMetadata])
-        .with_network_tests(&[&RestartValidator, &EmitTransaction])
+        .with_network_tests(&[&RestartValidator, &EmitTransaction::DEFAULT])
 }
 
 fn k8s_test_suite() -> ForgeConfig<'static> {
@@ -188,19 +390,66 @@ fn k8s_test_suite() -> ForgeConfig<'static> {
         .with_initial_validator_count(NonZeroUsize::new(30).unwrap())
         .with_public_usage_tests(&[&FundAccount, &TransferCoins])
         .with_admin_tests(&[&GetMetadata])
-        .with_network_tests(&[&EmitTransaction, &SimpleValidatorUpgrade])
+        .with_network_tests(&[&EmitTransaction::DEFAULT, &SimpleValidatorUpgrade])
+}
+
+/// A small and quick subset of `k8s_test_suite`, for sanity checking changes before landing
+fn smoke_test_suite() -> ForgeConfig<'static> {
+    ForgeConfig::default()
+        .with_initial_validator_count(NonZeroUsize::new(4).unwrap())
+        .with_public_usage_tests(&[&FundAccount, &TransferCoins])
+        .with_network_tests(&[&EmitTransaction::SMOKE])
+}
+
+/// All public usage tests, including the ones too niche for the default suites
+fn public_usage_test_suite() -> ForgeConfig<'static> {
+    ForgeConfig::default().with_public_usage_tests(&[
+        &FundAccount,
+        &TransferCoins,
+        &MismatchedChainId,
+    ])
+}
+
+/// All admin tests, including the ones too niche for the default suites
+fn admin_test_suite() -> ForgeConfig<'static> {
+    ForgeConfig::default().with_admin_tests(&[&GetMetadata, &RegisteredCurrencies])
 }
 
 fn land_blocking_test_suite() -> ForgeConfig<'static> {
     ForgeConfig::default()
         .with_initial_validator_count(NonZeroUsize::new(30).unwrap())
-        .with_network_tests(&[&PerformanceBenchmark])
+        .with_network_tests(&[&PerformanceBenchmark::XUS])
+}
+
+fn disk_pressure_test_suite() -> ForgeConfig<'static> {
+    ForgeConfig::default()
+        .with_initial_validator_count(NonZeroUsize::new(30).unwrap())
+        .with_network_tests(&[&DiskPressure])
+}
+
+fn clock_skew_test_suite() -> ForgeConfig<'static> {
+    ForgeConfig::default()
+        .with_initial_validator_count(NonZeroUsize::new(30).unwrap())
+        .with_network_tests(&[&ClockSkew])
+}
+
+/// Requires a standby validator, i.e. `--num-standby-validators` of at least 1
+fn validator_failover_test_suite() -> ForgeConfig<'static> {
+    ForgeConfig::default()
+        .with_initial_validator_count(NonZeroUsize::new(30).unwrap())
+        .with_network_tests(&[&ValidatorFailover])
+}
+
+fn epoch_transition_test_suite() -> ForgeConfig<'static> {
+    ForgeConfig::default()
+        .with_initial_validator_count(NonZeroUsize::new(30).unwrap())
+        .with_network_tests(&[&EpochTransitionUnderLoad])
 }
 
 fn land_blocking_test_compat_suite() -> ForgeConfig<'static> {
     ForgeConfig::default()
         .with_initial_validator_count(NonZeroUsize::new(30).unwrap())
-        .with_network_tests(&[&PerformanceBenchmark, &SimpleValidatorUpgrade])
+        .with_network_tests(&[&PerformanceBenchmark::XUS, &SimpleValidatorUpgrade])
 }
 
 //TODO Make public test later
@@ -230,6 +479,39 @@ impl AdminTest for GetMetadata {
     }
 }
 
+/// Currencies registered at genesis, with their scaling factors
+const GENESIS_CURRENCIES: &[(&str, u64)] = &[("XUS", 1_000_000), ("XDX", 1_000_000)];
+
+#[derive(Debug)]
+struct RegisteredCurrencies;
+
+impl Test for RegisteredCurrencies {
+    fn name(&self) -> &'static str {
+        "registered_currencies"
+    }
+}
+
+impl AdminTest for RegisteredCurrencies {
+    fn run<'t>(&self, ctx: &mut AdminContext<'t>) -> Result<()> {
+        let currencies = ctx.client().get_currencies()?.into_inner();
+        for (code, scaling_factor) in GENESIS_CURRENCIES {
+            let currency = currencies
+                .iter()
+                .find(|c| c.code == *code)
+                .ok_or_else(|| anyhow::anyhow!("Currency {} is not registered", code))?;
+            anyhow::ensure!(
+                currency.scaling_factor == *scaling_factor,
+                "Unexpected scaling factor for {}: expected {}, got {}",
+                code,
+                scaling_factor,
+                currency.scaling_factor
+            );
+        }
+
+        Ok(())
+    }
+}
+
 pub fn check_account_balance(
     client: &BlockingClient,
     currency: Currency,
@@ -307,13 +589,8 @@ impl PublicUsageTest for TransferCoins {
                 0,
             ),
         );
-        let batch = vec![
-            MethodRequest::submit(&create_payer)?,
-            MethodRequest::submit(&create_payee)?,
-        ];
-        client.batch(batch)?;
-        client.wait_for_signed_transaction(&create_payer, None, None)?;
-        client.wait_for_signed_transaction(&create_payee, None, None)?;
+        ctx.submit_and_await(&create_payer, None)?;
+        ctx.submit_and_await(&create_payee, None)?;
         check_account_balance(&client, currency, payer.address(), 100)?;
 
         ctx.transfer_coins(currency, &mut payer, payee.address(), 10)?;
@@ -331,6 +608,36 @@ impl PublicUsageTest for TransferCoins {
     }
 }
 
+#[derive(Debug)]
+struct MismatchedChainId;
+
+impl Test for MismatchedChainId {
+    fn name(&self) -> &'static str {
+        "mismatched_chain_id"
+    }
+}
+
+impl PublicUsageTest for MismatchedChainId {
+    fn run<'t>(&self, ctx: &mut PublicUsageContext<'t>) -> Result<()> {
+        let mut account = ctx.random_account();
+        let amount = 1000;
+        let currency = Currency::XUS;
+        let client = ctx.client();
+        ctx.create_parent_vasp_account(account.authentication_key())?;
+        ctx.fund(account.address(), amount)?;
+
+        let other_chain_id = ChainId::new(ctx.chain_id().id().wrapping_add(1));
+        ctx.assert_rejected_with_chain_id(&mut account, other_chain_id)?;
+
+        // The rejected transaction must not have been executed
+        check_account_balance(&client, currency, account.address(), amount)?;
+        let account_view = client.get_account(account.address())?.into_inner().unwrap();
+        assert_eq!(account_view.sequence_number, account.sequence_number());
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 struct RestartValidator;
 
@@ -356,23 +663,31 @@ impl NetworkTest for RestartValidator {
 }
 
 #[derive(Debug)]
-struct EmitTransaction;
+struct CrashLoopValidator;
 
-impl Test for EmitTransaction {
+impl Test for CrashLoopValidator {
     fn name(&self) -> &'static str {
-        "emit_transaction"
+        "crash_loop_validator"
     }
 }
 
-impl NetworkTest for EmitTransaction {
+impl NetworkTest for CrashLoopValidator {
     fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
-        let duration = Duration::from_secs(10);
+        let duration = Duration::from_secs(30);
+        let node = ctx.swarm().validators_mut().next().unwrap();
+        println!("Crash looping node {}", node.peer_id());
+        node.crash_loop(5, Duration::from_secs(10))?;
+
+        // The network, including the recovered node, must still make progress without forking
         let all_validators = ctx
             .swarm()
             .validators()
             .map(|v| v.peer_id())
             .collect::<Vec<_>>();
-        let stats = generate_traffic(ctx, &all_validators, duration).unwrap();
+        let stats = generate_traffic(ctx, &all_validators, duration)?;
+        ctx.swarm()
+            .wait_for_all_nodes_to_catchup(Instant::now() + Duration::from_secs(60))?;
+        ctx.swarm().fork_check()?;
         ctx.report
             .report_txn_stats(self.name().to_string(), stats, duration);
         ctx.report.print_report();
@@ -380,3 +695,67 @@ impl NetworkTest for EmitTransaction {
         Ok(())
     }
 }
+
+#[derive(Debug)]
+struct EmitTransaction {
+    name: &'static str,
+    /// Whether to sample the validators' mempool sizes while emitting
+    sample_mempool: bool,
+    duration: Duration,
+}
+
+impl EmitTransaction {
+    const DEFAULT: Self = Self {
+        name: "emit_transaction",
+        sample_mempool: false,
+        duration: Duration::from_secs(10),
+    };
+    const SAMPLING_MEMPOOL: Self = Self {
+        name: "emit_transaction_sampling_mempool",
+        sample_mempool: true,
+        duration: Duration::from_secs(10),
+    };
+    const SMOKE: Self = Self {
+        name: "emit_transaction_smoke",
+        sample_mempool: false,
+        duration: Duration::from_secs(30),
+    };
+}
+
+impl Test for EmitTransaction {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl NetworkTest for EmitTransaction {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
+        let duration = self.duration;
+        let all_validators = ctx
+            .swarm()
+            .validators()
+            .map(|v| v.peer_id())
+            .collect::<Vec<_>>();
+        if self.sample_mempool {
+            let (stats, mempool_stats) = generate_traffic_sampling_mempool(
+                ctx,
+                &all_validators,
+                duration,
+                Duration::from_secs(1),
+            )?;
+            ctx.report
+                .report_txn_stats(self.name().to_string(), stats, duration);
+            ctx.report
+                .report_mempool_size(self.name().to_string(), mempool_stats);
+        } else {
+            let stats = generate_traffic(ctx, &all_validators, duration).unwrap();
+            ctx.report
+                .report_txn_stats(self.name().to_string(), stats, duration);
+        }
+        // No validator may have fallen behind under the load
+        ctx.for_each_validator(|validator| validator.liveness_check(10))?;
+        ctx.report.print_report();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suites defined in a config file, so that custom suites can be maintained without editing
+//! `main.rs`. A config file maps suite names to the names of the tests they run, e.g.
+//!
+//! ```yaml
+//! my_suite:
+//!   initial_validator_count: 30
+//!   public_usage_tests: [fund_account, transfer_coins]
+//!   admin_tests: [get_metadata]
+//!   network_tests: [performance::performance-benchmark]
+//! ```
+
+use crate::{
+    CrashLoopValidator, EmitTransaction, FundAccount, GetMetadata, MismatchedChainId,
+    RegisteredCurrencies, RestartValidator, TransferCoins,
+};
+use anyhow::{bail, format_err, Context, Result};
+use forge::{AdminTest, ForgeConfig, NetworkTest, PublicUsageTest, Test};
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs, num::NonZeroUsize, path::Path};
+use testcases::{
+    clock_skew_test::ClockSkew,
+    compatibility_test::SimpleValidatorUpgrade,
+    disk_pressure_test::DiskPressure,
+    epoch_transition_test::EpochTransitionUnderLoad,
+    performance_test::{ModuleCallBenchmark, PerformanceBenchmark},
+    validator_failover_test::ValidatorFailover,
+};
+
+/// All tests which can be referenced from a suite config file.
+const PUBLIC_USAGE_TESTS: &[&dyn PublicUsageTest] =
+    &[&FundAccount, &TransferCoins, &MismatchedChainId];
+const ADMIN_TESTS: &[&dyn AdminTest] = &[&GetMetadata, &RegisteredCurrencies];
+const NETWORK_TESTS: &[&dyn NetworkTest] = &[
+    &RestartValidator,
+    &CrashLoopValidator,
+    &EmitTransaction::DEFAULT,
+    &EmitTransaction::SAMPLING_MEMPOOL,
+    &EmitTransaction::SMOKE,
+    &SimpleValidatorUpgrade,
+    &PerformanceBenchmark::XUS,
+    &ModuleCallBenchmark,
+    &DiskPressure,
+    &ClockSkew,
+    &ValidatorFailover,
+    &EpochTransitionUnderLoad,
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SuiteConfig {
+    initial_validator_count: Option<NonZeroUsize>,
+    #[serde(default)]
+    public_usage_tests: Vec<String>,
+    #[serde(default)]
+    admin_tests: Vec<String>,
+    #[serde(default)]
+    network_tests: Vec<String>,
+}
+
+/// Builds the `ForgeConfig` for the suite `suite_name` defined in the config file at `path`.
+///
+/// This leaks the three lists of tests of the suite, which is intentional: `ForgeConfig` only
+/// borrows its tests, and `forge_main` needs `'static` slices of them, which a suite built at
+/// runtime can't provide otherwise. A suite is loaded once per process, so the leak is bounded.
+pub fn load_test_suite(path: &Path, suite_name: &str) -> Result<ForgeConfig<'static>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read suite config {}", path.display()))?;
+    let mut suites: BTreeMap<String, SuiteConfig> = serde_yaml::from_str(&contents)
+        .with_context(|| format!("Failed to parse suite config {}", path.display()))?;
+    let suite = suites.remove(suite_name).ok_or_else(|| {
+        format_err!(
+            "Suite `{}` is not defined in {}, available suites: {:?}",
+            suite_name,
+            path.display(),
+            suites.keys().collect::<Vec<_>>()
+        )
+    })?;
+
+    let public_usage_tests = lookup_tests(PUBLIC_USAGE_TESTS, &suite.public_usage_tests)?;
+    let admin_tests = lookup_tests(ADMIN_TESTS, &suite.admin_tests)?;
+    let network_tests = lookup_tests(NETWORK_TESTS, &suite.network_tests)?;
+    let mut config = ForgeConfig::default()
+        .with_public_usage_tests(Box::leak(public_usage_tests.into_boxed_slice()))
+        .with_admin_tests(Box::leak(admin_tests.into_boxed_slice()))
+        .with_network_tests(Box::leak(network_tests.into_boxed_slice()));
+    if let Some(count) = suite.initial_validator_count {
+        config = config.with_initial_validator_count(count);
+    }
+    Ok(config)
+}
+
+fn lookup_tests<T: Test + ?Sized>(
+    registry: &[&'static T],
+    names: &[String],
+) -> Result<Vec<&'static T>> {
+    names
+        .iter()
+        .map(
+            |name| match registry.iter().find(|test| test.name() == name.as_str()) {
+                Some(test) => Ok(*test),
+                None => bail!(
+                    "Unknown test `{}`, known tests of this kind: {:?}",
+                    name,
+                    registry.iter().map(|test| test.name()).collect::<Vec<_>>()
+                ),
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const CONFIG: &str = "\
+smoke:
+  initial_validator_count: 4
+  public_usage_tests: [fund_account]
+  network_tests: [emit_transaction_smoke]
+admin:
+  admin_tests: [get_metadata, registered_currencies]
+";
+
+    fn config_file(contents: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    fn test_names(config: &ForgeConfig) -> Vec<&'static str> {
+        config.all_tests().map(|test| test.name()).collect()
+    }
+
+    #[test]
+    fn test_load_test_suite() {
+        let file = config_file(CONFIG);
+        let smoke = load_test_suite(file.path(), "smoke").unwrap();
+        assert_eq!(
+            test_names(&smoke),
+            vec!["fund_account", "emit_transaction_smoke"]
+        );
+        let admin = load_test_suite(file.path(), "admin").unwrap();
+        assert_eq!(
+            test_names(&admin),
+            vec!["get_metadata", "registered_currencies"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_suite() {
+        let file = config_file(CONFIG);
+        let err = load_test_suite(file.path(), "nightly").unwrap_err();
+        assert!(err.to_string().contains("Suite `nightly` is not defined"));
+    }
+
+    #[test]
+    fn test_unknown_test() {
+        // Tests are looked up among the tests of their kind only
+        let file = config_file("suite:\n  admin_tests: [fund_account]\n");
+        let err = load_test_suite(file.path(), "suite").unwrap_err();
+        assert!(err.to_string().contains("Unknown test `fund_account`"));
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let file = config_file("suite:\n  tests: [fund_account]\n");
+        load_test_suite(file.path(), "suite").unwrap_err();
+    }
+}
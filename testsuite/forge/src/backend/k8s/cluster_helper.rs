@@ -32,7 +32,7 @@ use tempfile::TempDir;
 use tokio::runtime::Runtime;
 
 const HELM_BIN: &str = "helm";
-const KUBECTL_BIN: &str = "kubectl";
+pub(crate) const KUBECTL_BIN: &str = "kubectl";
 const MAX_NUM_VALIDATORS: usize = 30;
 const HEALTH_CHECK_URL: &str = "http://127.0.0.1:8001";
 
@@ -186,6 +186,64 @@ pub fn uninstall_from_k8s_cluster() -> Result<()> {
     Ok(())
 }
 
+/// The helm values `clean_k8s_cluster` installs on top of the values of the existing validator
+/// and testnet releases.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HelmValues {
+    pub era: String,
+    pub num_validators: usize,
+    pub validator_image_tag: String,
+    pub testnet_image_tag: String,
+}
+
+impl HelmValues {
+    pub fn new(
+        era: String,
+        num_validators: usize,
+        validator_image_tag: String,
+        testnet_image_tag: String,
+    ) -> Self {
+        Self {
+            era,
+            num_validators,
+            validator_image_tag,
+            testnet_image_tag,
+        }
+    }
+
+    /// The `--set` overrides for each validator release
+    pub fn validator_overrides(&self) -> Vec<String> {
+        vec![
+            format!("chain.era={}", self.era),
+            format!("imageTag={}", self.validator_image_tag),
+            "loggingToNull=true".to_string(),
+        ]
+    }
+
+    /// The `--set` overrides for the testnet release
+    pub fn testnet_overrides(&self) -> Vec<String> {
+        vec![
+            format!("genesis.era={}", self.era),
+            format!("genesis.numValidators={}", self.num_validators),
+            format!("imageTag={}", self.testnet_image_tag),
+            "monitoring.prometheus.useHttps=false".to_string(),
+        ]
+    }
+}
+
+/// Builds the helm upgrade options which apply `overrides` on top of the values in `values_file`
+fn helm_upgrade_options(values_file: &str, overrides: &[String]) -> Vec<String> {
+    let mut options: Vec<String> = ["-f", values_file, "--install", "--history-max", "2"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    for value in overrides {
+        options.push("--set".to_string());
+        options.push(value.clone());
+    }
+    options
+}
+
 pub fn clean_k8s_cluster(
     helm_repo: String,
     base_num_validators: usize,
@@ -193,23 +251,36 @@ pub fn clean_k8s_cluster(
     base_testnet_image_tag: String,
     require_validator_healthcheck: bool,
 ) -> Result<()> {
-    assert!(base_num_validators <= MAX_NUM_VALIDATORS);
+    let values = HelmValues::new(
+        get_new_era()?,
+        base_num_validators,
+        base_validator_image_tag,
+        base_testnet_image_tag,
+    );
+    apply_helm_values(&helm_repo, &values, require_validator_healthcheck)
+}
 
-    let new_era = get_new_era().unwrap();
+/// Re-installs the validator and testnet releases with the given `values`, starting a new era
+pub fn apply_helm_values(
+    helm_repo: &str,
+    values: &HelmValues,
+    require_validator_healthcheck: bool,
+) -> Result<()> {
+    assert!(values.num_validators <= MAX_NUM_VALIDATORS);
 
     let tmp_dir = TempDir::new().expect("Could not create temp dir");
 
     // prepare for scale up. get the helm values to upgrade later
-    (0..base_num_validators).into_par_iter().for_each(|i| {
+    (0..values.num_validators).into_par_iter().for_each(|i| {
         let v: Value = get_helm_status(&format!("val{}", i)).unwrap();
         let version = v["version"].as_i64().expect("not a i64") as usize;
         let config = &v["config"];
 
         let era: &str = &era_to_string(&v["config"]["chain"]["era"]).unwrap();
         assert!(
-            !&new_era.eq(era),
+            values.era != era,
             "New era {} is the same as past release era {}",
-            new_era,
+            values.era,
             era
         );
 
@@ -225,26 +296,19 @@ pub fn clean_k8s_cluster(
     println!("All validators prepare for upgrade");
 
     // upgrade validators in parallel
-    (0..base_num_validators).into_par_iter().for_each(|i| {
+    let validator_overrides = values.validator_overrides();
+    (0..values.num_validators).into_par_iter().for_each(|i| {
         let file_path = tmp_dir
             .path()
             .join(format!("val{}_status.json", i))
             .display()
             .to_string();
-        let validator_upgrade_options = [
-            "-f",
-            &file_path,
-            "--install",
-            "--history-max",
-            "2",
-            "--set",
-            &format!("chain.era={}", &new_era),
-            "--set",
-            &format!("imageTag={}", &base_validator_image_tag),
-            "--set",
-            "loggingToNull=true",
-        ];
-        upgrade_validator(&format!("val{}", i), &helm_repo, &validator_upgrade_options).unwrap();
+        let validator_upgrade_options = helm_upgrade_options(&file_path, &validator_overrides);
+        let validator_upgrade_options: Vec<&str> = validator_upgrade_options
+            .iter()
+            .map(String::as_str)
+            .collect();
+        upgrade_validator(&format!("val{}", i), helm_repo, &validator_upgrade_options).unwrap();
     });
     println!("All validators upgraded");
 
@@ -267,31 +331,19 @@ pub fn clean_k8s_cluster(
         .join("diem_status.json")
         .display()
         .to_string();
-    let testnet_upgrade_options = [
-        "-f",
-        &file_path_str,
-        "--install",
-        "--history-max",
-        "2",
-        "--set",
-        &format!("genesis.era={}", &new_era),
-        "--set",
-        &format!("genesis.numValidators={}", base_num_validators),
-        "--set",
-        &format!("imageTag={}", &base_testnet_image_tag),
-        "--set",
-        "monitoring.prometheus.useHttps=false",
-    ];
+    let testnet_upgrade_options = helm_upgrade_options(&file_path_str, &values.testnet_overrides());
+    let testnet_upgrade_options: Vec<&str> =
+        testnet_upgrade_options.iter().map(String::as_str).collect();
 
     // upgrade testnet
-    upgrade_testnet(&helm_repo, &testnet_upgrade_options)?;
+    upgrade_testnet(helm_repo, &testnet_upgrade_options)?;
 
     // wait for genesis to run again, and get the updated validators
     let rt = Runtime::new().unwrap();
     let mut validators = rt.block_on(async {
         let kube_client = create_k8s_client().await;
-        wait_genesis_job(&kube_client, &new_era).await.unwrap();
-        let vals = get_validators(kube_client.clone(), &base_validator_image_tag)
+        wait_genesis_job(&kube_client, &values.era).await.unwrap();
+        let vals = get_validators(kube_client.clone(), &values.validator_image_tag)
             .await
             .unwrap();
         vals
@@ -516,3 +568,52 @@ pub fn set_eks_nodegroup_size(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_helm_upgrade_options() {
+        let values = HelmValues::new(
+            "fg1".to_string(),
+            4,
+            "validator_tag".to_string(),
+            "testnet_tag".to_string(),
+        );
+        assert_eq!(
+            helm_upgrade_options("values.json", &values.validator_overrides()),
+            vec![
+                "-f",
+                "values.json",
+                "--install",
+                "--history-max",
+                "2",
+                "--set",
+                "chain.era=fg1",
+                "--set",
+                "imageTag=validator_tag",
+                "--set",
+                "loggingToNull=true",
+            ]
+        );
+        assert_eq!(
+            helm_upgrade_options("values.json", &values.testnet_overrides()),
+            vec![
+                "-f",
+                "values.json",
+                "--install",
+                "--history-max",
+                "2",
+                "--set",
+                "genesis.era=fg1",
+                "--set",
+                "genesis.numValidators=4",
+                "--set",
+                "imageTag=testnet_tag",
+                "--set",
+                "monitoring.prometheus.useHttps=false",
+            ]
+        );
+    }
+}
@@ -27,6 +27,7 @@ pub struct K8sFactory {
     helm_repo: String,
     image_tag: String,
     base_image_tag: String,
+    num_standby_validators: usize,
 }
 
 impl K8sFactory {
@@ -78,8 +79,16 @@ impl K8sFactory {
             helm_repo,
             image_tag,
             base_image_tag,
+            num_standby_validators: 0,
         })
     }
+
+    /// Provisions `num_standby_validators` validators in addition to the requested node count,
+    /// which are registered on chain but kept out of the validator set until promoted.
+    pub fn with_num_standby_validators(mut self, num_standby_validators: usize) -> Self {
+        self.num_standby_validators = num_standby_validators;
+        self
+    }
 }
 
 impl Factory for K8sFactory {
@@ -93,17 +102,20 @@ impl Factory for K8sFactory {
         node_num: NonZeroUsize,
         version: &Version,
     ) -> Result<Box<dyn Swarm>> {
-        set_eks_nodegroup_size(self.cluster_name.clone(), node_num.get(), true)?;
+        // Standby validators go through genesis like the others, since promoting a validator
+        // requires its ValidatorConfig to be on chain, and are removed from the set afterwards
+        let total_num = node_num.get() + self.num_standby_validators;
+        set_eks_nodegroup_size(self.cluster_name.clone(), total_num, true)?;
         uninstall_from_k8s_cluster()?;
         clean_k8s_cluster(
             self.helm_repo.clone(),
-            node_num.get(),
+            total_num,
             format!("{}", version),
             DEFAULT_TESTNET_IMAGE_TAG.to_string(),
             true,
         )?;
         let rt = Runtime::new().unwrap();
-        let swarm = rt
+        let mut swarm = rt
             .block_on(K8sSwarm::new(
                 &self.root_key,
                 &self.treasury_compliance_key,
@@ -113,6 +125,7 @@ impl Factory for K8sFactory {
                 &self.base_image_tag,
             ))
             .unwrap();
+        swarm.provision_standby_validators(self.num_standby_validators)?;
         Ok(Box::new(swarm))
     }
 }
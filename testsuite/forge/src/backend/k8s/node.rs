@@ -1,17 +1,37 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{FullNode, HealthCheckError, Node, Result, Validator, Version};
-use anyhow::format_err;
+use crate::{
+    ClockOffset, FullNode, HealthCheckError, Node, Result, Validator, Version, KUBECTL_BIN,
+};
+use anyhow::{bail, format_err};
 use diem_config::config::NodeConfig;
-use diem_sdk::{client::Client as JsonRpcClient, types::PeerId};
+use diem_sdk::{
+    client::Client as JsonRpcClient,
+    types::{state_proof::StateProof, waypoint::Waypoint, PeerId},
+};
 use reqwest::Url;
 use std::{
+    convert::TryFrom,
     fmt::{Debug, Formatter},
+    process::Command,
     str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
 };
 use tokio::runtime::Runtime;
 
+/// The node's data volume
+const DATA_DIR: &str = "/opt/diem/data";
+/// File in the node's data directory which is used to consume disk space
+const DISK_FILLER_PATH: &str = "/opt/diem/data/forge-disk-filler";
+/// libfaketime installed in the validator image (see docker/validator/Dockerfile), preloaded to
+/// skew the clock of the node process. Containers share the host's kernel clock, so the skew
+/// can't be applied to the pod itself.
+const LIBFAKETIME_PATH: &str = "/usr/lib/x86_64-linux-gnu/faketime/libfaketime.so.1";
+/// Maximum difference between the requested and the observed clock skew, to account for the
+/// latency of kubectl and clock differences between the host running forge and the cluster.
+const CLOCK_OFFSET_TOLERANCE_SECS: i64 = 30;
+
 pub struct K8sNode {
     pub(crate) name: String,
     pub(crate) peer_id: PeerId,
@@ -45,6 +65,43 @@ impl K8sNode {
     pub(crate) fn json_rpc_client(&self) -> JsonRpcClient {
         JsonRpcClient::new(self.json_rpc_endpoint().to_string())
     }
+
+    fn stateful_set(&self) -> String {
+        format!("statefulset/{}-diem-validator-validator", self.name)
+    }
+
+    /// Returns the current time in the node's container, in seconds since the epoch, as seen by
+    /// processes started with the container's environment
+    fn container_time_secs(&self) -> Result<i64> {
+        let stateful_set = self.stateful_set();
+        let output = self.kubectl_output(&["exec", &stateful_set, "--", "date", "+%s"])?;
+        output.trim().parse::<i64>().map_err(|e| {
+            format_err!(
+                "Unexpected date output for {}: {}: {}",
+                self.name,
+                output,
+                e
+            )
+        })
+    }
+
+    fn kubectl(&self, args: &[&str]) -> Result<()> {
+        self.kubectl_output(args).map(|_| ())
+    }
+
+    /// Runs kubectl with `args`, returning its stdout
+    fn kubectl_output(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new(KUBECTL_BIN).args(args).output()?;
+        if !output.status.success() {
+            bail!(
+                "kubectl {:?} failed for {}: {}",
+                args,
+                self.name,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
 }
 
 impl Node for K8sNode {
@@ -72,6 +129,22 @@ impl Node for K8sNode {
         todo!()
     }
 
+    fn genesis_waypoint(&self) -> Result<Waypoint> {
+        // The epoch change proof starting at version 0 begins with the ledger info which ends the
+        // genesis epoch, from which the genesis waypoint is derived
+        let state_proof_view = self
+            .runtime
+            .block_on(self.json_rpc_client().get_state_proof(0))?
+            .into_inner();
+        let state_proof = StateProof::try_from(&state_proof_view)?;
+        let genesis_li = state_proof
+            .epoch_changes()
+            .ledger_info_with_sigs
+            .first()
+            .ok_or_else(|| format_err!("{} returned an empty epoch change proof", self.name))?;
+        Waypoint::new_epoch_boundary(genesis_li.ledger_info())
+    }
+
     fn start(&mut self) -> Result<()> {
         todo!()
     }
@@ -80,10 +153,92 @@ impl Node for K8sNode {
         todo!()
     }
 
+    fn restart(&mut self) -> Result<()> {
+        let stateful_set = self.stateful_set();
+        self.kubectl(&["rollout", "restart", &stateful_set])?;
+        self.kubectl(&["rollout", "status", "--watch", &stateful_set])
+    }
+
     fn clear_storage(&mut self) -> Result<()> {
         todo!()
     }
 
+    fn kill(&mut self) -> Result<()> {
+        // Force deleting the pod skips the graceful shutdown, and the stateful set recreates it
+        let pod = format!("pod/{}-diem-validator-validator-0", self.name);
+        self.kubectl(&["delete", &pod, "--grace-period=0", "--force"])
+    }
+
+    fn free_disk(&mut self) -> Result<u64> {
+        let stateful_set = self.stateful_set();
+        let output = self.kubectl_output(&[
+            "exec",
+            &stateful_set,
+            "--",
+            "df",
+            "--block-size=1",
+            "--output=avail",
+            DATA_DIR,
+        ])?;
+        // The first line is the header
+        output
+            .lines()
+            .nth(1)
+            .and_then(|line| line.trim().parse::<u64>().ok())
+            .ok_or_else(|| format_err!("Unexpected df output for {}: {}", self.name, output))
+    }
+
+    fn consume_disk(&mut self, bytes: u64) -> Result<()> {
+        let stateful_set = self.stateful_set();
+        let size = bytes.to_string();
+        self.kubectl(&[
+            "exec",
+            &stateful_set,
+            "--",
+            "fallocate",
+            "-l",
+            &size,
+            DISK_FILLER_PATH,
+        ])
+    }
+
+    fn release_disk(&mut self) -> Result<()> {
+        let stateful_set = self.stateful_set();
+        self.kubectl(&["exec", &stateful_set, "--", "rm", "-f", DISK_FILLER_PATH])
+    }
+
+    fn set_clock_offset(&mut self, offset: ClockOffset) -> Result<()> {
+        let stateful_set = self.stateful_set();
+        let offset_secs = match offset {
+            ClockOffset::Ahead(offset) => offset.as_secs() as i64,
+            ClockOffset::Behind(offset) => -(offset.as_secs() as i64),
+        };
+        let faketime = format!("FAKETIME={:+}s", offset_secs);
+        let preload = format!("LD_PRELOAD={}", LIBFAKETIME_PATH);
+        // Changing the environment of the stateful set rolls out new pods
+        self.kubectl(&["set", "env", &stateful_set, &faketime, &preload])?;
+        self.kubectl(&["rollout", "status", "--watch", &stateful_set])?;
+
+        // If libfaketime can't be preloaded, processes still start, with the real time
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let observed_offset_secs = self.container_time_secs()? - now;
+        if (observed_offset_secs - offset_secs).abs() > CLOCK_OFFSET_TOLERANCE_SECS {
+            bail!(
+                "Clock of {} is skewed by {}s instead of {}s",
+                self.name,
+                observed_offset_secs,
+                offset_secs
+            );
+        }
+        Ok(())
+    }
+
+    fn clear_clock_offset(&mut self) -> Result<()> {
+        let stateful_set = self.stateful_set();
+        self.kubectl(&["set", "env", &stateful_set, "FAKETIME-", "LD_PRELOAD-"])?;
+        self.kubectl(&["rollout", "status", "--watch", &stateful_set])
+    }
+
     fn health_check(&mut self) -> Result<(), HealthCheckError> {
         let results = match self
             .runtime
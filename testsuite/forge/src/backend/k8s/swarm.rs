@@ -10,9 +10,15 @@ use anyhow::{anyhow, bail, format_err};
 use diem_config::config::NodeConfig;
 use diem_logger::*;
 use diem_sdk::{
+    client::BlockingClient,
     crypto::ed25519::Ed25519PrivateKey,
+    transaction_builder::TransactionFactory,
     types::{
+        account_config::diem_root_address,
+        account_state::AccountState,
+        account_state_blob::AccountStateBlob,
         chain_id::{ChainId, NamedChain},
+        validator_config::ValidatorConfigResource,
         AccountKey, LocalAccount, PeerId,
     },
 };
@@ -29,6 +35,7 @@ const VALIDATOR_LB: &str = "validator-fullnode-lb";
 
 pub struct K8sSwarm {
     validators: HashMap<PeerId, K8sNode>,
+    standby_validators: HashMap<PeerId, K8sNode>,
     fullnodes: HashMap<PeerId, K8sNode>,
     root_account: LocalAccount,
     treasury_compliance_account: LocalAccount,
@@ -104,6 +111,7 @@ impl K8sSwarm {
 
         Ok(Self {
             validators,
+            standby_validators: HashMap::new(),
             fullnodes,
             root_account,
             treasury_compliance_account,
@@ -129,6 +137,89 @@ impl K8sSwarm {
     fn get_kube_client(&self) -> K8sClient {
         self.kube_client.clone()
     }
+
+    /// Removes the `num` validators with the highest node ids from the validator set, leaving
+    /// them running as standby validators which can be promoted later on.
+    pub(crate) fn provision_standby_validators(&mut self, num: usize) -> Result<()> {
+        if num == 0 {
+            return Ok(());
+        }
+        if num >= self.validators.len() {
+            bail!(
+                "Cannot provision {} standby validators out of {} validators",
+                num,
+                self.validators.len()
+            );
+        }
+        self.resolve_peer_ids()?;
+        let mut ids = self
+            .validators
+            .values()
+            .map(|v| (v.node_id, v.peer_id()))
+            .collect::<Vec<_>>();
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+        for (_, id) in ids.into_iter().take(num) {
+            self.demote_to_standby(id)?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the placeholder PeerIds of the validators with their on-chain account addresses.
+    /// Validators are matched by the human name of their ValidatorConfig, which genesis sets to
+    /// the name of the helm release.
+    fn resolve_peer_ids(&mut self) -> Result<()> {
+        let client = BlockingClient::new(self.get_url());
+        let blob = client
+            .get_account_state_with_proof(diem_root_address(), None, None)?
+            .into_inner()
+            .blob
+            .ok_or_else(|| anyhow!("No account state found for the diem root account"))?;
+        let validator_set = AccountState::try_from(&bcs::from_bytes::<AccountStateBlob>(&blob)?)?
+            .get_validator_set()?
+            .ok_or_else(|| anyhow!("No validator set found on chain"))?;
+        let mut addresses = HashMap::new();
+        for info in validator_set.payload() {
+            let address = *info.account_address();
+            let human_name = client
+                .get_deserialized_resource::<ValidatorConfigResource>(address)?
+                .into_inner()
+                .ok_or_else(|| anyhow!("No validator config found for {}", address))?
+                .human_name;
+            addresses.insert(String::from_utf8(human_name)?, address);
+        }
+        let validators = std::mem::take(&mut self.validators);
+        for (_, mut node) in validators {
+            node.peer_id = *addresses.get(node.name()).ok_or_else(|| {
+                anyhow!(
+                    "No validator named {} found in the validator set",
+                    node.name()
+                )
+            })?;
+            self.validators.insert(node.peer_id(), node);
+        }
+        Ok(())
+    }
+
+    /// Adds the validator with the provided PeerId to the validator set if `add` is set, and
+    /// removes it otherwise. Returns once the reconfiguration has been committed.
+    fn update_validator_set(&mut self, id: PeerId, add: bool) -> Result<()> {
+        let client = BlockingClient::new(self.get_url());
+        let validator_name = client
+            .get_deserialized_resource::<ValidatorConfigResource>(id)?
+            .into_inner()
+            .ok_or_else(|| anyhow!("No validator config found for {}", id))?
+            .human_name;
+        let factory = TransactionFactory::new(self.chain_id);
+        let allowed_nonce = 0;
+        let txn = self.root_account.sign_with_transaction_builder(if add {
+            factory.add_validator_and_reconfigure(allowed_nonce, validator_name, id)
+        } else {
+            factory.remove_validator_and_reconfigure(allowed_nonce, validator_name, id)
+        });
+        client.submit(&txn)?;
+        client.wait_for_signed_transaction(&txn, None, None)?;
+        Ok(())
+    }
 }
 
 impl Drop for K8sSwarm {
@@ -211,6 +302,34 @@ impl Swarm for K8sSwarm {
         remove_helm_release(self.validator(id).unwrap().name())
     }
 
+    fn standby_validators<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn Validator> + 'a> {
+        Box::new(
+            self.standby_validators
+                .values()
+                .map(|v| v as &'a dyn Validator),
+        )
+    }
+
+    fn promote_to_validator(&mut self, id: PeerId) -> Result<()> {
+        if !self.standby_validators.contains_key(&id) {
+            bail!("Invalid standby id: {}", id);
+        }
+        self.update_validator_set(id, true)?;
+        let node = self.standby_validators.remove(&id).unwrap();
+        self.validators.insert(id, node);
+        Ok(())
+    }
+
+    fn demote_to_standby(&mut self, id: PeerId) -> Result<()> {
+        if !self.validators.contains_key(&id) {
+            bail!("Invalid id: {}", id);
+        }
+        self.update_validator_set(id, false)?;
+        let node = self.validators.remove(&id).unwrap();
+        self.standby_validators.insert(id, node);
+        Ok(())
+    }
+
     fn add_full_node(&mut self, _version: &Version, _template: NodeConfig) -> Result<PeerId> {
         todo!()
     }
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{Factory, Result, Swarm, Version};
-use anyhow::Context;
+use anyhow::{bail, Context};
+use diem_config::config::NodeConfig;
+use diem_logger::Level;
 use rand::rngs::StdRng;
 use std::{
     collections::HashMap,
@@ -36,15 +38,35 @@ impl LocalVersion {
 
 pub struct LocalFactory {
     versions: Arc<HashMap<Version, LocalVersion>>,
+    node_log_level: Option<Level>,
+    node_configs: Option<Vec<NodeConfig>>,
 }
 
 impl LocalFactory {
     pub fn new(versions: HashMap<Version, LocalVersion>) -> Self {
         Self {
             versions: Arc::new(versions),
+            node_log_level: None,
+            node_configs: None,
         }
     }
 
+    /// Create a LocalFactory with the diem-node built from the current workspace, which launches
+    /// one validator per config in `node_configs`, e.g. to reproduce config-dependent bugs with
+    /// a deterministic topology. See `LocalSwarmBuilder::node_configs` for which parts of the
+    /// configs are used.
+    pub fn from_configs(node_configs: Vec<NodeConfig>) -> Result<Self> {
+        let mut factory = Self::from_workspace()?;
+        factory.node_configs = Some(node_configs);
+        Ok(factory)
+    }
+
+    /// Run the nodes of every launched swarm at `level`, overriding `RUST_LOG`.
+    pub fn with_node_log_level(mut self, level: Level) -> Self {
+        self.node_log_level = Some(level);
+        self
+    }
+
     pub fn from_workspace() -> Result<Self> {
         let mut versions = HashMap::new();
         let new_version = cargo::get_diem_node_binary_from_worktree().map(|(revision, bin)| {
@@ -128,10 +150,23 @@ impl Factory for LocalFactory {
         node_num: NonZeroUsize,
         version: &Version,
     ) -> Result<Box<dyn Swarm>> {
-        let mut swarm = LocalSwarm::builder(self.versions.clone())
+        let mut builder = LocalSwarm::builder(self.versions.clone())
             .number_of_validators(node_num)
-            .initial_version(version.clone())
-            .build(rng)?;
+            .initial_version(version.clone());
+        if let Some(node_configs) = &self.node_configs {
+            if node_configs.len() != node_num.get() {
+                bail!(
+                    "Requested {} validators, but the factory has configs for {}",
+                    node_num,
+                    node_configs.len()
+                );
+            }
+            builder = builder.node_configs(node_configs.clone());
+        }
+        if let Some(level) = self.node_log_level {
+            builder = builder.node_log_level(level);
+        }
+        let mut swarm = builder.build(rng)?;
         swarm
             .launch()
             .with_context(|| format!("Swarm logs can be found here: {}", swarm.logs_location()))?;
@@ -1,13 +1,15 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{FullNode, HealthCheckError, LocalVersion, Node, NodeExt, Validator, Version};
-use anyhow::{anyhow, Context, Result};
+use crate::{
+    ClockOffset, FullNode, HealthCheckError, LocalVersion, Node, NodeExt, Validator, Version,
+};
+use anyhow::{anyhow, bail, Context, Result};
 use diem_config::config::NodeConfig;
-use diem_logger::{debug, warn};
+use diem_logger::{debug, warn, Level};
 use diem_sdk::{
     client::BlockingClient,
-    types::{account_address::AccountAddress, PeerId},
+    types::{account_address::AccountAddress, waypoint::Waypoint, PeerId},
 };
 use std::{
     env,
@@ -46,6 +48,7 @@ pub struct LocalNode {
     peer_id: AccountAddress,
     directory: PathBuf,
     config: NodeConfig,
+    log_level: Option<Level>,
 }
 
 impl LocalNode {
@@ -64,9 +67,16 @@ impl LocalNode {
             peer_id,
             directory,
             config,
+            log_level: None,
         })
     }
 
+    /// Set the level the node logs at from its next start on. Without it, the node logs at
+    /// `RUST_LOG` if that is set, and at debug otherwise.
+    pub fn set_log_level(&mut self, level: Level) {
+        self.log_level = Some(level);
+    }
+
     pub fn config_path(&self) -> PathBuf {
         self.directory.join("node.yaml")
     }
@@ -96,7 +106,9 @@ impl LocalNode {
             .current_dir(&self.directory)
             .arg("-f")
             .arg(self.config_path());
-        if env::var("RUST_LOG").is_err() {
+        if let Some(level) = self.log_level {
+            node_command.env("RUST_LOG", level.to_string());
+        } else if env::var("RUST_LOG").is_err() {
             // Only set our RUST_LOG if its not present in environment
             node_command.env("RUST_LOG", "debug");
         }
@@ -204,6 +216,10 @@ impl Node for LocalNode {
         self.config()
     }
 
+    fn genesis_waypoint(&self) -> Result<Waypoint> {
+        Ok(self.config().base.waypoint.genesis_waypoint())
+    }
+
     fn start(&mut self) -> Result<()> {
         self.start()
     }
@@ -217,6 +233,33 @@ impl Node for LocalNode {
         todo!()
     }
 
+    fn kill(&mut self) -> Result<()> {
+        // Dropping the process kills it. There is no supervisor in a local swarm, so restart
+        // the node in its place.
+        self.stop();
+        self.start()
+    }
+
+    fn free_disk(&mut self) -> Result<u64> {
+        bail!("Querying the free disk space is not supported by LocalNode")
+    }
+
+    fn consume_disk(&mut self, _bytes: u64) -> Result<()> {
+        bail!("Consuming disk space is not supported by LocalNode")
+    }
+
+    fn release_disk(&mut self) -> Result<()> {
+        bail!("Releasing disk space is not supported by LocalNode")
+    }
+
+    fn set_clock_offset(&mut self, _offset: ClockOffset) -> Result<()> {
+        bail!("Skewing the clock is not supported by LocalNode")
+    }
+
+    fn clear_clock_offset(&mut self) -> Result<()> {
+        bail!("Skewing the clock is not supported by LocalNode")
+    }
+
     fn health_check(&mut self) -> Result<(), HealthCheckError> {
         self.health_check()
     }
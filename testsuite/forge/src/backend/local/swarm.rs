@@ -5,9 +5,10 @@ use crate::{
     ChainInfo, FullNode, HealthCheckError, LocalNode, LocalVersion, Node, Swarm, SwarmExt,
     Validator, Version,
 };
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use diem_config::config::NodeConfig;
 use diem_genesis_tool::{fullnode_builder::FullnodeConfig, validator_builder::ValidatorBuilder};
+use diem_logger::Level;
 use diem_sdk::{
     crypto::ed25519::Ed25519PrivateKey,
     types::{
@@ -85,6 +86,8 @@ pub struct LocalSwarmBuilder {
     template: NodeConfig,
     number_of_validators: NonZeroUsize,
     dir: Option<PathBuf>,
+    node_log_level: Option<Level>,
+    node_configs: Option<Vec<NodeConfig>>,
 }
 
 impl LocalSwarmBuilder {
@@ -95,6 +98,8 @@ impl LocalSwarmBuilder {
             template: NodeConfig::default_for_validator(),
             number_of_validators: NonZeroUsize::new(1).unwrap(),
             dir: None,
+            node_log_level: None,
+            node_configs: None,
         }
     }
 
@@ -118,6 +123,19 @@ impl LocalSwarmBuilder {
         self
     }
 
+    /// Launch one validator per config, built from that config instead of the common template.
+    /// Keys, storage, and genesis are still generated, but everything else, including the ports,
+    /// is taken from the configs. This overrides `template` and `number_of_validators`.
+    pub fn node_configs(mut self, node_configs: Vec<NodeConfig>) -> Self {
+        self.node_configs = Some(node_configs);
+        self
+    }
+
+    pub fn node_log_level(mut self, level: Level) -> Self {
+        self.node_log_level = Some(level);
+        self
+    }
+
     pub fn build<R>(self, rng: R) -> Result<LocalSwarm>
     where
         R: ::rand::RngCore + ::rand::CryptoRng,
@@ -132,13 +150,17 @@ impl LocalSwarmBuilder {
             SwarmDirectory::Temporary(TempDir::new()?)
         };
 
-        let (root_keys, genesis, genesis_waypoint, validators) = ValidatorBuilder::new(
+        let builder = ValidatorBuilder::new(
             &dir,
             diem_framework_releases::current_module_blobs().to_vec(),
-        )
-        .num_validators(self.number_of_validators)
-        .template(self.template)
-        .build(rng)?;
+        );
+        let builder = match self.node_configs {
+            Some(node_configs) => builder.validator_templates(node_configs),
+            None => builder
+                .num_validators(self.number_of_validators)
+                .template(self.template),
+        };
+        let (root_keys, genesis, genesis_waypoint, validators) = builder.build(rng)?;
 
         // Get the initial version to start the nodes with, either the one provided or fallback to
         // using the the latest version
@@ -153,10 +175,14 @@ impl LocalSwarmBuilder {
         });
         let version = versions.get(&initial_version).unwrap();
 
+        let node_log_level = self.node_log_level;
         let validators = validators
             .into_iter()
             .map(|v| {
-                let node = LocalNode::new(version.to_owned(), v.name, v.directory)?;
+                let mut node = LocalNode::new(version.to_owned(), v.name, v.directory)?;
+                if let Some(level) = node_log_level {
+                    node.set_log_level(level);
+                }
                 Ok((node.peer_id(), node))
             })
             .collect::<Result<HashMap<_, _>>>()?;
@@ -201,6 +227,7 @@ impl LocalSwarmBuilder {
             treasury_compliance_account,
             designated_dealer_account,
             chain_id: ChainId::test(),
+            node_log_level,
         })
     }
 }
@@ -219,6 +246,7 @@ pub struct LocalSwarm {
     treasury_compliance_account: LocalAccount,
     designated_dealer_account: LocalAccount,
     chain_id: ChainId,
+    node_log_level: Option<Level>,
 }
 
 impl LocalSwarm {
@@ -226,6 +254,12 @@ impl LocalSwarm {
         LocalSwarmBuilder::new(versions)
     }
 
+    /// The waypoint of the genesis this swarm was built with, which its nodes are configured to
+    /// trust. Unlike `SwarmExt::waypoint`, this doesn't require querying the nodes.
+    pub fn genesis_waypoint(&self) -> Waypoint {
+        self.genesis_waypoint
+    }
+
     pub fn launch(&mut self) -> Result<()> {
         // Start all the validators
         for validator in self.validators.values_mut() {
@@ -294,6 +328,9 @@ impl LocalSwarm {
             fullnode_config.name,
             fullnode_config.directory,
         )?;
+        if let Some(level) = self.node_log_level {
+            fullnode.set_log_level(level);
+        }
 
         let peer_id = fullnode.peer_id();
         fullnode.start()?;
@@ -372,6 +409,18 @@ impl Swarm for LocalSwarm {
         todo!()
     }
 
+    fn standby_validators<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn Validator> + 'a> {
+        Box::new(std::iter::empty())
+    }
+
+    fn promote_to_validator(&mut self, _id: PeerId) -> Result<()> {
+        bail!("Promoting standby validators is not supported by LocalSwarm")
+    }
+
+    fn demote_to_standby(&mut self, _id: PeerId) -> Result<()> {
+        bail!("Demoting validators to standby is not supported by LocalSwarm")
+    }
+
     fn add_full_node(&mut self, version: &Version, template: NodeConfig) -> Result<PeerId> {
         self.add_fullnode(version, template)
     }
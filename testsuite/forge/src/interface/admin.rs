@@ -3,7 +3,20 @@
 
 use super::{ChainInfo, CoreContext, Test};
 use crate::Result;
-use diem_sdk::{client::BlockingClient, types::LocalAccount};
+use anyhow::bail;
+use diem_sdk::{
+    client::{views::TransactionView, BlockingClient, WaitForTransactionError},
+    transaction_builder::{TransactionBuilder, TransactionFactory},
+    types::{vm_status::StatusCode, LocalAccount},
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Minimum delay between two submission attempts of the same admin transaction, so that retries
+/// don't flood the network while other admin activity settles.
+const ADMIN_RETRY_DELAY: Duration = Duration::from_millis(500);
 
 /// The testing interface which defines a test written from the perspective of the Admin of the
 /// network. This means that the test will have access to the Root account but do not control any
@@ -13,6 +26,13 @@ pub trait AdminTest: Test {
     fn run<'t>(&self, ctx: &mut AdminContext<'t>) -> Result<()>;
 }
 
+/// The privileged accounts which can sign admin transactions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdminAccount {
+    Root,
+    TreasuryCompliance,
+}
+
 #[derive(Debug)]
 pub struct AdminContext<'t> {
     core: CoreContext,
@@ -44,4 +64,72 @@ impl<'t> AdminContext<'t> {
     pub fn random_account(&mut self) -> LocalAccount {
         LocalAccount::generate(self.core.rng())
     }
+
+    /// Signs the transaction built by `build` with `account`, submits it and waits until it is
+    /// committed and executed successfully, or until `timeout` elapses.
+    ///
+    /// Other admin activity can use up the sequence number of `account` concurrently. In that
+    /// case the sequence number is refreshed from the chain and the transaction is rebuilt and
+    /// resubmitted, which is why it is passed as a builder function.
+    pub fn submit_admin_and_confirm<F>(
+        &mut self,
+        account: AdminAccount,
+        build: F,
+        timeout: Duration,
+    ) -> Result<TransactionView>
+    where
+        F: Fn(&TransactionFactory) -> TransactionBuilder,
+    {
+        let deadline = Instant::now() + timeout;
+        let client = self.client();
+        let factory = self.chain_info.transaction_factory();
+        let account = match account {
+            AdminAccount::Root => self.chain_info.root_account(),
+            AdminAccount::TreasuryCompliance => self.chain_info.treasury_compliance_account(),
+        };
+
+        loop {
+            let attempt_start = Instant::now();
+            let txn = account.sign_with_transaction_builder(build(&factory));
+
+            match client.submit(&txn) {
+                Ok(_) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    match client.wait_for_signed_transaction(&txn, Some(remaining), None) {
+                        Ok(response) => return Ok(response.into_inner()),
+                        // Another transaction was committed with our sequence number
+                        Err(WaitForTransactionError::TransactionHashMismatchError(_)) => {}
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                Err(e) => {
+                    let status = e.json_rpc_error().and_then(|e| e.as_status_code());
+                    if !matches!(
+                        status,
+                        Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD)
+                            | Some(StatusCode::SEQUENCE_NUMBER_TOO_NEW)
+                    ) {
+                        *account.sequence_number_mut() -= 1;
+                        return Err(e.into());
+                    }
+                }
+            }
+
+            if Instant::now() + ADMIN_RETRY_DELAY >= deadline {
+                bail!(
+                    "Timed out submitting admin transaction from {} due to sequence number races",
+                    account.address()
+                );
+            }
+            let elapsed = attempt_start.elapsed();
+            if elapsed < ADMIN_RETRY_DELAY {
+                thread::sleep(ADMIN_RETRY_DELAY - elapsed);
+            }
+            *account.sequence_number_mut() = client
+                .get_account(account.address())?
+                .into_inner()
+                .map(|view| view.sequence_number)
+                .unwrap_or(0);
+        }
+    }
 }
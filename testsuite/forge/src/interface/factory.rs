@@ -17,3 +17,18 @@ pub trait Factory {
         version: &Version,
     ) -> Result<Box<dyn Swarm>>;
 }
+
+impl<F: Factory + ?Sized> Factory for &F {
+    fn versions<'a>(&'a self) -> Box<dyn Iterator<Item = Version> + 'a> {
+        (**self).versions()
+    }
+
+    fn launch_swarm(
+        &self,
+        rng: &mut StdRng,
+        node_num: NonZeroUsize,
+        version: &Version,
+    ) -> Result<Box<dyn Swarm>> {
+        (**self).launch_swarm(rng, node_num, version)
+    }
+}
@@ -2,7 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::Test;
-use crate::{CoreContext, Result, Swarm, TestReport};
+use crate::{CircuitBreaker, CoreContext, Result, Swarm, TestReport, Validator};
+use anyhow::anyhow;
+use diem_sdk::move_types::account_address::AccountAddress;
+use rayon::prelude::*;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 /// The testing interface which defines a test written with full control over an existing network.
 /// Tests written against this interface will have access to both the Root account as well as the
@@ -16,14 +23,27 @@ pub struct NetworkContext<'t> {
     core: CoreContext,
     swarm: &'t mut dyn Swarm,
     pub report: TestReport,
+    emitter_account_cache: Option<PathBuf>,
+    emitter_circuit_breaker: Option<CircuitBreaker>,
+    block_timeline_dir: Option<PathBuf>,
 }
 
 impl<'t> NetworkContext<'t> {
-    pub fn new(core: CoreContext, swarm: &'t mut dyn Swarm, report: TestReport) -> Self {
+    pub fn new(
+        core: CoreContext,
+        swarm: &'t mut dyn Swarm,
+        report: TestReport,
+        emitter_account_cache: Option<PathBuf>,
+        emitter_circuit_breaker: Option<CircuitBreaker>,
+        block_timeline_dir: Option<PathBuf>,
+    ) -> Self {
         Self {
             core,
             swarm,
             report,
+            emitter_account_cache,
+            emitter_circuit_breaker,
+            block_timeline_dir,
         }
     }
 
@@ -34,4 +54,65 @@ impl<'t> NetworkContext<'t> {
     pub fn core(&mut self) -> &mut CoreContext {
         &mut self.core
     }
+
+    /// Runs `f` against all validators concurrently, returning the results in the order of
+    /// `swarm().validators()`. If `f` fails for any validator, returns a single error which
+    /// lists the failures of all validators.
+    pub fn for_each_validator<F, T>(&self, f: F) -> Result<Vec<T>>
+    where
+        F: Fn(&dyn Validator) -> Result<T> + Sync,
+        T: Send,
+    {
+        let validators = self.swarm.validators().collect::<Vec<_>>();
+        let results = validators
+            .par_iter()
+            .map(|validator| (validator.peer_id(), f(*validator)))
+            .collect::<Vec<_>>();
+
+        let mut values = Vec::with_capacity(results.len());
+        let mut failures = Vec::new();
+        for (peer_id, result) in results {
+            match result {
+                Ok(value) => values.push(value),
+                Err(e) => failures.push(format!("{}: {:#}", peer_id, e)),
+            }
+        }
+        if !failures.is_empty() {
+            return Err(anyhow!(
+                "{} of {} validators failed: {}",
+                failures.len(),
+                validators.len(),
+                failures.join(", ")
+            ));
+        }
+
+        Ok(values)
+    }
+
+    /// Waits until the on-chain sequence number of `address` reaches `target`, e.g. once all
+    /// transactions submitted from that account were committed.
+    pub fn wait_for_sequence_number(
+        &mut self,
+        address: AccountAddress,
+        target: u64,
+        timeout: Duration,
+    ) -> Result<()> {
+        let client = self.swarm.chain_info().json_rpc_client();
+        super::public::wait_for_sequence_number(&client, address, target, timeout)
+    }
+
+    /// The file in which transaction emitters persist their accounts across runs, if any.
+    pub fn emitter_account_cache(&self) -> Option<&Path> {
+        self.emitter_account_cache.as_deref()
+    }
+
+    /// The circuit breaker which transaction emitters use to stop early on a stalled network.
+    pub fn emitter_circuit_breaker(&self) -> Option<CircuitBreaker> {
+        self.emitter_circuit_breaker
+    }
+
+    /// The directory to which tests export the timeline of the blocks they committed, if any.
+    pub fn block_timeline_dir(&self) -> Option<&Path> {
+        self.block_timeline_dir.as_deref()
+    }
 }
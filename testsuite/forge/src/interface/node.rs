@@ -1,20 +1,28 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{Result, Version};
+use crate::{collect_block_timeline, BlockTimelineEntry, Result, Version};
 use debug_interface::NodeDebugClient;
 use diem_config::{config::NodeConfig, network_id::NetworkId};
 use diem_sdk::{
     client::{BlockingClient, Client as JsonRpcClient},
-    types::PeerId,
+    types::{waypoint::Waypoint, PeerId},
 };
 use std::{
     collections::HashMap,
+    ops::Range,
     thread,
     time::{Duration, Instant},
 };
 use url::Url;
 
+/// How far a Node's clock is skewed relative to the real time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockOffset {
+    Ahead(Duration),
+    Behind(Duration),
+}
+
 #[derive(Debug)]
 pub enum HealthCheckError {
     NotRunning,
@@ -30,8 +38,9 @@ impl std::fmt::Display for HealthCheckError {
 
 impl std::error::Error for HealthCheckError {}
 
-/// Trait used to represent a running Validator or FullNode
-pub trait Node {
+/// Trait used to represent a running Validator or FullNode. Nodes are `Send + Sync`, so that
+/// operations can be run against several of them concurrently.
+pub trait Node: Send + Sync {
     /// Return the PeerId of this Node
     fn peer_id(&self) -> PeerId;
 
@@ -50,6 +59,9 @@ pub trait Node {
     /// Return a reference to the Config this Node is using
     fn config(&self) -> &NodeConfig;
 
+    /// Return the Waypoint of the genesis this Node booted from
+    fn genesis_waypoint(&self) -> Result<Waypoint>;
+
     /// Start this Node.
     /// This should be a noop if the Node is already running.
     fn start(&mut self) -> Result<()>;
@@ -67,6 +79,29 @@ pub trait Node {
     /// Clears this Node's Storage
     fn clear_storage(&mut self) -> Result<()>;
 
+    /// Forcefully kills this Node's process without a graceful shutdown, in order to simulate a
+    /// crash. The Node is then restarted by its supervisor, as it would be after a real crash.
+    fn kill(&mut self) -> Result<()>;
+
+    /// Returns the free space on this Node's data volume, in bytes.
+    fn free_disk(&mut self) -> Result<u64>;
+
+    /// Consumes `bytes` bytes of this Node's data volume, in order to simulate disk pressure.
+    /// The space can be released again using Node::release_disk
+    fn consume_disk(&mut self, bytes: u64) -> Result<()>;
+
+    /// Releases the space consumed by Node::consume_disk.
+    /// This should be a noop if no space has been consumed.
+    fn release_disk(&mut self) -> Result<()>;
+
+    /// Skews this Node's clock by `offset`, in order to test tolerance to clock skew. This
+    /// restarts the Node. The skew can be removed again using Node::clear_clock_offset
+    fn set_clock_offset(&mut self, offset: ClockOffset) -> Result<()>;
+
+    /// Restores this Node's clock to the real time, restarting the Node.
+    /// This should be a noop if no offset has been set.
+    fn clear_clock_offset(&mut self) -> Result<()>;
+
     /// Performs a Health Check on the Node
     fn health_check(&mut self) -> Result<(), HealthCheckError>;
 }
@@ -129,28 +164,12 @@ pub trait NodeExt: Node {
         metric_name: &str,
         fields: HashMap<String, String>,
     ) -> Result<Option<i64>> {
-        let filtered: Vec<_> = self
-            .debug_client()
-            .get_node_metric_with_name(metric_name)?
-            .into_iter()
-            .flat_map(|map| map.into_iter())
-            .filter_map(|(metric, metric_value)| {
-                if fields
-                    .iter()
-                    .all(|(key, value)| metric.contains(&format!("{}={}", key, value)))
-                {
-                    Some(metric_value)
-                } else {
-                    None
-                }
-            })
-            .collect();
+        get_metric_with_fields(&self.debug_client(), metric_name, fields)
+    }
 
-        Ok(if filtered.is_empty() {
-            None
-        } else {
-            Some(filtered.iter().sum())
-        })
+    /// Query the number of transactions in this Node's mempool
+    fn mempool_size(&self) -> Result<Option<i64>> {
+        mempool_size(&self.debug_client())
     }
 
     fn get_connected_peers(
@@ -166,6 +185,12 @@ pub trait NodeExt: Node {
         self.get_metric_with_fields("diem_connections", map)
     }
 
+    /// Return a timeline of the blocks this Node committed within `versions`, e.g. to look for
+    /// stalls or bursts after a benchmark
+    fn block_timeline(&self, versions: Range<u64>) -> Result<Vec<BlockTimelineEntry>> {
+        collect_block_timeline(&self.json_rpc_client(), versions)
+    }
+
     fn liveness_check(&self, seconds: u64) -> Result<()> {
         let mut url = self.json_rpc_endpoint();
         url.set_path("-/healthy");
@@ -183,6 +208,17 @@ pub trait NodeExt: Node {
         Ok(())
     }
 
+    /// Kills this Node `count` times, `interval` apart, to simulate a crash-looping process.
+    /// Afterwards, waits for the Node to recover and become healthy again.
+    fn crash_loop(&mut self, count: usize, interval: Duration) -> Result<()> {
+        for i in 0..count {
+            println!("Killing node {} ({} of {})", self.name(), i + 1, count);
+            self.kill()?;
+            thread::sleep(interval);
+        }
+        self.wait_until_healthy(Instant::now() + Duration::from_secs(120))
+    }
+
     fn wait_until_healthy(&mut self, deadline: Instant) -> Result<()> {
         while Instant::now() < deadline {
             match self.health_check() {
@@ -207,3 +243,42 @@ pub trait NodeExt: Node {
         ))
     }
 }
+
+/// Query the sum of the values of the metric `metric_name` whose labels match all `fields`.
+/// Unlike `NodeExt::get_metric_with_fields`, this doesn't need to borrow the Node, so it can be
+/// used from another thread while the Node is in use.
+pub fn get_metric_with_fields(
+    client: &NodeDebugClient,
+    metric_name: &str,
+    fields: HashMap<String, String>,
+) -> Result<Option<i64>> {
+    let filtered: Vec<_> = client
+        .get_node_metric_with_name(metric_name)?
+        .into_iter()
+        .flat_map(|map| map.into_iter())
+        .filter_map(|(metric, metric_value)| {
+            if fields
+                .iter()
+                .all(|(key, value)| metric.contains(&format!("{}={}", key, value)))
+            {
+                Some(metric_value)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    Ok(if filtered.is_empty() {
+        None
+    } else {
+        Some(filtered.iter().sum())
+    })
+}
+
+/// Query the number of transactions in the mempool of the Node behind `client`. Every
+/// transaction in mempool is tracked by the system TTL index, so its size is the mempool size.
+pub fn mempool_size(client: &NodeDebugClient) -> Result<Option<i64>> {
+    let mut fields = HashMap::new();
+    fields.insert("index".to_string(), "system_ttl".to_string());
+    get_metric_with_fields(client, "diem_core_mempool_index_size", fields)
+}
@@ -3,11 +3,20 @@
 
 use super::Test;
 use crate::{CoreContext, Result};
+use anyhow::bail;
 use diem_sdk::{
-    client::{BlockingClient, FaucetClient},
+    client::{views::TransactionView, BlockingClient, FaucetClient, WaitForTransactionError},
     move_types::account_address::AccountAddress,
     transaction_builder::{Currency, TransactionFactory},
-    types::{chain_id::ChainId, transaction::authenticator::AuthenticationKey, LocalAccount},
+    types::{
+        chain_id::ChainId,
+        transaction::{authenticator::AuthenticationKey, SignedTransaction},
+        LocalAccount,
+    },
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
 };
 
 /// The testing interface which defines a test written from the perspective of the a public user of
@@ -57,6 +66,59 @@ impl<'t> PublicUsageContext<'t> {
         TransactionFactory::new(self.chain_id())
     }
 
+    /// Submits a transaction from `sender` built for `chain_id` and checks that it is rejected by
+    /// the network. `chain_id` is expected to differ from the chain id of the network under test.
+    pub fn assert_rejected_with_chain_id(
+        &self,
+        sender: &mut LocalAccount,
+        chain_id: ChainId,
+    ) -> Result<()> {
+        let tx = sender.sign_with_transaction_builder(
+            TransactionFactory::new(chain_id).peer_to_peer(Currency::XUS, sender.address(), 0),
+        );
+        // The transaction is never executed, so undo the sequence number bump from signing.
+        *sender.sequence_number_mut() -= 1;
+        if self.client().submit(&tx).is_ok() {
+            bail!(
+                "transaction for chain id {} was accepted by chain {}",
+                chain_id,
+                self.chain_id()
+            );
+        }
+        Ok(())
+    }
+
+    /// Submits `txn` and waits up to `timeout` for it to be committed, returning the committed
+    /// transaction. Fails with the VM status if the transaction was not executed successfully.
+    pub fn submit_and_await(
+        &self,
+        txn: &SignedTransaction,
+        timeout: Option<Duration>,
+    ) -> Result<TransactionView> {
+        let client = self.client();
+        client.submit(txn)?;
+        match client.wait_for_signed_transaction(txn, timeout, None) {
+            Ok(response) => Ok(response.into_inner()),
+            Err(WaitForTransactionError::TransactionExecutionFailed(txn_view)) => bail!(
+                "transaction {} failed with vm status: {}",
+                txn_view.hash,
+                txn_view.vm_status
+            ),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Waits until the on-chain sequence number of `address` reaches `target`, e.g. once all
+    /// transactions submitted from that account were committed.
+    pub fn wait_for_sequence_number(
+        &self,
+        address: AccountAddress,
+        target: u64,
+        timeout: Duration,
+    ) -> Result<()> {
+        wait_for_sequence_number(&self.client(), address, target, timeout)
+    }
+
     pub fn fund(&mut self, address: AccountAddress, amount: u64) -> Result<()> {
         self.public_info.coffer.fund(Currency::XUS, address, amount)
     }
@@ -80,18 +142,45 @@ impl<'t> PublicUsageContext<'t> {
         payee: AccountAddress,
         amount: u64,
     ) -> Result<()> {
-        let client = self.client();
         let tx = sender.sign_with_transaction_builder(
             self.transaction_factory()
                 .peer_to_peer(currency, payee, amount),
         );
-        client.submit(&tx)?;
-        client.wait_for_signed_transaction(&tx, None, None)?;
+        self.submit_and_await(&tx, None)?;
 
         Ok(())
     }
 }
 
+/// Polls the sequence number of `address` until it reaches `target`, failing with the last seen
+/// sequence number after `timeout`. An account which doesn't exist yet has sequence number 0.
+pub(super) fn wait_for_sequence_number(
+    client: &BlockingClient,
+    address: AccountAddress,
+    target: u64,
+    timeout: Duration,
+) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let sequence_number = client
+            .get_account(address)?
+            .into_inner()
+            .map_or(0, |account| account.sequence_number);
+        if sequence_number >= target {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            bail!(
+                "timed out waiting for sequence number {} of account {}, last seen {}",
+                target,
+                address,
+                sequence_number
+            );
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+}
+
 pub enum Coffer<'t> {
     TreasuryCompliance {
         transaction_factory: TransactionFactory,
@@ -4,8 +4,13 @@
 use crate::{ChainInfo, FullNode, NodeExt, Result, Validator, Version};
 use anyhow::anyhow;
 use diem_config::config::NodeConfig;
-use diem_sdk::{client::BlockingClient, types::PeerId};
+use diem_sdk::{
+    client::BlockingClient,
+    crypto::HashValue,
+    types::{waypoint::Waypoint, PeerId},
+};
 use std::{
+    collections::HashMap,
     thread,
     time::{Duration, Instant},
 };
@@ -49,6 +54,18 @@ pub trait Swarm {
     /// Removes the Validator with the provided PeerId
     fn remove_validator(&mut self, id: PeerId) -> Result<()>;
 
+    /// Returns an Iterator of references to all the standby Validators in the Swarm. A standby
+    /// Validator is running, but is not part of the validator set
+    fn standby_validators<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn Validator> + 'a>;
+
+    /// Promotes the standby Validator with the provided PeerId to an active Validator by adding
+    /// it to the validator set
+    fn promote_to_validator(&mut self, id: PeerId) -> Result<()>;
+
+    /// Demotes the Validator with the provided PeerId to a standby Validator by removing it from
+    /// the validator set. The node keeps running, so that it can be promoted again later
+    fn demote_to_standby(&mut self, id: PeerId) -> Result<()>;
+
     /// Adds a FullNode to the swarm with the provided PeerId
     fn add_full_node(&mut self, version: &Version, template: NodeConfig) -> Result<PeerId>;
 
@@ -64,6 +81,16 @@ pub trait Swarm {
     fn logs_location(&mut self) -> String;
 }
 
+/// The committed state of a single Validator, as captured by `SwarmExt::coordinated_snapshot`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeSnapshot {
+    pub peer_id: PeerId,
+    /// The version at which the snapshot was taken, which is common to all nodes in a snapshot
+    pub version: u64,
+    /// The root hash of the transaction accumulator committed at `version`
+    pub accumulator_root_hash: HashValue,
+}
+
 impl<T: ?Sized> SwarmExt for T where T: Swarm {}
 
 pub trait SwarmExt: Swarm {
@@ -168,6 +195,129 @@ pub trait SwarmExt: Swarm {
         Ok(())
     }
 
+    /// Perform a safety check at a specific `version`, ensuring that all validators agree on the
+    /// ledger state committed at that version. Unlike `fork_check`, which looks at the latest
+    /// state, this can be used to inspect a version at which a transient fork is suspected.
+    fn assert_agreement_at(&self, version: u64) -> Result<()> {
+        let mut root_hashes: HashMap<_, Vec<PeerId>> = HashMap::new();
+        for validator in self.validators() {
+            let root_hash = validator
+                .json_rpc_client()
+                .get_metadata_by_version(version)?
+                .into_inner()
+                .accumulator_root_hash;
+            root_hashes
+                .entry(root_hash)
+                .or_default()
+                .push(validator.peer_id());
+        }
+
+        if root_hashes.len() > 1 {
+            let divergence = root_hashes
+                .iter()
+                .map(|(hash, peers)| format!("{}: {:?}", hash, peers))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(anyhow!(
+                "Validators disagree on the ledger state at version {}: {}",
+                version,
+                divergence
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Ensure that all validators booted from the same genesis, by comparing their genesis
+    /// waypoints.
+    fn check_genesis_consistency(&self) -> Result<()> {
+        self.waypoint().map(|_| ())
+    }
+
+    /// Return the genesis waypoint of the swarm, i.e. the one all validators booted from. Fails
+    /// if the validators don't agree on it, as there is no single waypoint to trust then.
+    fn waypoint(&self) -> Result<Waypoint> {
+        // Waypoint isn't hashable, so group the validators by a linear search instead
+        let mut waypoints: Vec<(Waypoint, Vec<PeerId>)> = Vec::new();
+        for validator in self.validators() {
+            let waypoint = validator.genesis_waypoint()?;
+            match waypoints.iter_mut().find(|(w, _)| *w == waypoint) {
+                Some((_, peers)) => peers.push(validator.peer_id()),
+                None => waypoints.push((waypoint, vec![validator.peer_id()])),
+            }
+        }
+
+        if waypoints.len() > 1 {
+            let divergence = waypoints
+                .iter()
+                .map(|(waypoint, peers)| format!("{}: {:?}", waypoint, peers))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(anyhow!(
+                "Validators booted from different geneses: {}",
+                divergence
+            ));
+        }
+
+        waypoints
+            .pop()
+            .map(|(waypoint, _)| waypoint)
+            .ok_or_else(|| anyhow!("Swarm has no validators to get the waypoint from"))
+    }
+
+    /// Capture the committed state of all validators at a common version, e.g. to inspect a
+    /// divergence after a `fork_check` failure. The swarm has no control over transaction
+    /// emitters, so `pause_traffic` is called first to stop them, e.g. by stopping the emit job.
+    /// This then waits for the validators to quiesce, i.e. for their latest versions to stop
+    /// advancing, and captures each validator's state at the highest version which all of them
+    /// have committed.
+    fn coordinated_snapshot<F>(&self, pause_traffic: F) -> Result<Vec<NodeSnapshot>>
+    where
+        F: FnOnce() -> Result<()>,
+    {
+        pause_traffic()?;
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let validators = self
+            .validators()
+            .map(|node| (node.peer_id(), node.json_rpc_client()))
+            .collect::<Vec<_>>();
+        let latest_versions = || {
+            validators
+                .iter()
+                .map(|(_, client)| client.get_metadata().map(|r| r.into_inner().version))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let mut versions = latest_versions()?;
+        loop {
+            thread::sleep(Duration::from_secs(1));
+            let next_versions = latest_versions()?;
+            if next_versions == versions {
+                break;
+            }
+            if Instant::now() > deadline {
+                return Err(anyhow!("waiting for validators to quiesce timed out"));
+            }
+            versions = next_versions;
+        }
+
+        let version = versions
+            .iter()
+            .min()
+            .copied()
+            .ok_or_else(|| anyhow!("Unable to query validators for their latest version"))?;
+        let mut snapshots = Vec::new();
+        for (peer_id, client) in validators.iter() {
+            let metadata = client.get_metadata_by_version(version)?.into_inner();
+            snapshots.push(NodeSnapshot {
+                peer_id: *peer_id,
+                version,
+                accumulator_root_hash: metadata.accumulator_root_hash,
+            });
+        }
+        Ok(snapshots)
+    }
+
     /// Waits for all nodes to have caught up to the specified `verison`.
     fn wait_for_all_nodes_to_catchup_to_version(
         &self,
@@ -0,0 +1,155 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Result;
+use diem_sdk::{
+    client::{
+        views::{EventDataView, TransactionDataView},
+        BlockingClient,
+    },
+    types::account_address::AccountAddress,
+};
+use std::{io::Write, ops::Range};
+
+/// Maximum number of transactions the JSON-RPC endpoint returns per `get_transactions` request
+const MAX_TRANSACTIONS_PER_REQUEST: u64 = 1000;
+
+/// A committed block, as reconstructed from the transactions of a node's ledger
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockTimelineEntry {
+    /// Version of the block metadata transaction which starts the block
+    pub version: u64,
+    pub round: u64,
+    pub proposer: AccountAddress,
+    pub timestamp_usecs: u64,
+    /// Number of user transactions committed in the block
+    pub num_transactions: u64,
+    /// Time between the proposal of the previous block and this one, if the previous block is
+    /// part of the timeline. Long intervals point at stalls. This is not the commit latency of
+    /// the block, which the ledger doesn't record.
+    pub proposal_interval_ms: Option<u64>,
+}
+
+/// Collect a timeline of the blocks which start within `versions`, by querying the ledger of
+/// the node behind `client`. The transaction count of the last block only covers the
+/// transactions within `versions`.
+pub fn collect_block_timeline(
+    client: &BlockingClient,
+    versions: Range<u64>,
+) -> Result<Vec<BlockTimelineEntry>> {
+    let mut timeline: Vec<BlockTimelineEntry> = vec![];
+    let mut start = versions.start;
+    while start < versions.end {
+        let limit = std::cmp::min(versions.end - start, MAX_TRANSACTIONS_PER_REQUEST);
+        let txns = client.get_transactions(start, limit, true)?.into_inner();
+        if txns.is_empty() {
+            break;
+        }
+        start += txns.len() as u64;
+
+        for txn in txns {
+            match txn.transaction {
+                TransactionDataView::BlockMetadata { timestamp_usecs } => {
+                    let (round, proposer) = txn
+                        .events
+                        .iter()
+                        .find_map(|event| match event.data {
+                            EventDataView::NewBlock {
+                                round, proposer, ..
+                            } => Some((round, proposer)),
+                            _ => None,
+                        })
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "Block metadata transaction {} has no new block event",
+                                txn.version
+                            )
+                        })?;
+                    let proposal_interval_ms = timeline.last().map(|previous| {
+                        timestamp_usecs.saturating_sub(previous.timestamp_usecs) / 1000
+                    });
+                    timeline.push(BlockTimelineEntry {
+                        version: txn.version,
+                        round,
+                        proposer,
+                        timestamp_usecs,
+                        num_transactions: 0,
+                        proposal_interval_ms,
+                    });
+                }
+                TransactionDataView::UserTransaction { .. } => {
+                    // User transactions before the first block of the range belong to a block
+                    // which started before it, and aren't accounted for
+                    if let Some(block) = timeline.last_mut() {
+                        block.num_transactions += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(timeline)
+}
+
+/// Write `timeline` as CSV with a header row, one row per block
+pub fn write_block_timeline_csv<W: Write>(
+    timeline: &[BlockTimelineEntry],
+    writer: &mut W,
+) -> Result<()> {
+    writeln!(
+        writer,
+        "version,round,proposer,timestamp_usecs,num_transactions,proposal_interval_ms"
+    )?;
+    for block in timeline {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{}",
+            block.version,
+            block.round,
+            block.proposer,
+            block.timestamp_usecs,
+            block.num_transactions,
+            block
+                .proposal_interval_ms
+                .map(|ms| ms.to_string())
+                .unwrap_or_default()
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_write_block_timeline_csv() {
+        let block = |version, round, timestamp_usecs, num_transactions, proposal_interval_ms| {
+            BlockTimelineEntry {
+                version,
+                round,
+                proposer: AccountAddress::new([1; AccountAddress::LENGTH]),
+                timestamp_usecs,
+                num_transactions,
+                proposal_interval_ms,
+            }
+        };
+        let timeline = vec![
+            block(10, 3, 1_000_000, 5, None),
+            block(16, 4, 1_250_000, 0, Some(250)),
+        ];
+        let mut csv = vec![];
+        write_block_timeline_csv(&timeline, &mut csv).unwrap();
+
+        let proposer = AccountAddress::new([1; AccountAddress::LENGTH]);
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            format!(
+                "version,round,proposer,timestamp_usecs,num_transactions,proposal_interval_ms\n\
+                 10,3,{0},1000000,5,\n\
+                 16,4,{0},1250000,0,250\n",
+                proposer
+            )
+        );
+    }
+}
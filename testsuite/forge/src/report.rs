@@ -10,6 +10,29 @@ pub struct TestReport {
     text: String,
 }
 
+/// Summary of the mempool sizes of a set of validators, sampled over the course of a test
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MempoolSizeStats {
+    pub min: i64,
+    pub avg: f64,
+    pub max: i64,
+    pub num_samples: usize,
+}
+
+impl MempoolSizeStats {
+    pub fn from_samples(samples: &[i64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        Self {
+            min: *samples.iter().min().unwrap(),
+            avg: samples.iter().sum::<i64>() as f64 / samples.len() as f64,
+            max: *samples.iter().max().unwrap(),
+            num_samples: samples.len(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ReportedMetric {
     pub test_name: String,
@@ -30,6 +53,10 @@ impl TestReport {
         });
     }
 
+    pub fn metrics(&self) -> &[ReportedMetric] {
+        &self.metrics
+    }
+
     pub fn report_text(&mut self, text: String) {
         if !self.text.is_empty() {
             self.text.push('\n');
@@ -63,6 +90,16 @@ impl TestReport {
         ));
     }
 
+    pub fn report_mempool_size(&mut self, test_name: String, stats: MempoolSizeStats) {
+        self.report_metric(test_name.clone(), "min_mempool_size", stats.min as f64);
+        self.report_metric(test_name.clone(), "avg_mempool_size", stats.avg);
+        self.report_metric(test_name.clone(), "max_mempool_size", stats.max as f64);
+        self.report_text(format!(
+            "{} : mempool size min {}, avg {:.1}, max {} over {} samples",
+            test_name, stats.min, stats.avg, stats.max, stats.num_samples
+        ));
+    }
+
     pub fn print_report(&self) {
         println!("Test Statistics: {}", self,);
     }
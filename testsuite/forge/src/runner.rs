@@ -3,10 +3,14 @@
 
 use crate::*;
 use rand::{Rng, SeedableRng};
+use serde::Serialize;
 use std::{
+    fs,
     io::{self, Write},
     num::NonZeroUsize,
+    path::{Path, PathBuf},
     process,
+    time::Instant,
 };
 use structopt::{clap::arg_enum, StructOpt};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
@@ -47,6 +51,13 @@ pub struct Options {
     ///   (json is unsupported, exists for compatibility with the default test harness)
     #[structopt(long, possible_values = &Format::variants(), default_value, case_insensitive = true)]
     format: Format,
+    #[structopt(long)]
+    /// Check that all validators booted from the same genesis before running any tests
+    check_genesis: bool,
+    #[structopt(long, parse(from_os_str))]
+    /// Write a JSON summary of the test results (counts of passed, failed and skipped tests, and
+    /// the total duration) to the given file
+    summary: Option<PathBuf>,
 }
 
 impl Options {
@@ -71,6 +82,21 @@ impl Default for Format {
 }
 
 pub fn forge_main<F: Factory>(tests: ForgeConfig<'_>, factory: F, options: &Options) -> Result<()> {
+    forge_main_with_reports(tests, factory, options, |_| Ok(()))
+}
+
+/// Same as `forge_main`, but hands the reports of the network tests to `check_reports` once all
+/// tests passed. An error returned by `check_reports` fails the run like a failed test.
+pub fn forge_main_with_reports<F, C>(
+    tests: ForgeConfig<'_>,
+    factory: F,
+    options: &Options,
+    check_reports: C,
+) -> Result<()>
+where
+    F: Factory,
+    C: FnOnce(Vec<TestReport>) -> Result<()>,
+{
     let forge = Forge::new(options, tests, factory);
 
     if options.list {
@@ -79,7 +105,7 @@ pub fn forge_main<F: Factory>(tests: ForgeConfig<'_>, factory: F, options: &Opti
         return Ok(());
     }
 
-    match forge.run() {
+    match forge.run_checking_reports(check_reports) {
         Ok(()) => Ok(()),
         Err(e) => {
             eprintln!("Failed to run tests:\n{}", e);
@@ -94,6 +120,7 @@ pub enum InitialVersion {
     Newest,
 }
 
+#[derive(Clone)]
 pub struct ForgeConfig<'cfg> {
     public_usage_tests: &'cfg [&'cfg dyn PublicUsageTest],
     admin_tests: &'cfg [&'cfg dyn AdminTest],
@@ -104,6 +131,15 @@ pub struct ForgeConfig<'cfg> {
 
     /// The initial version to use when the test harness creates a swarm
     initial_version: InitialVersion,
+
+    /// The file in which transaction emitters persist their accounts across runs
+    emitter_account_cache: Option<PathBuf>,
+
+    /// Stops transaction emitters early if the network stops making progress
+    emitter_circuit_breaker: Option<CircuitBreaker>,
+
+    /// The directory to which tests export the timeline of the blocks committed during the test
+    block_timeline_dir: Option<PathBuf>,
 }
 
 impl<'cfg> ForgeConfig<'cfg> {
@@ -139,6 +175,21 @@ impl<'cfg> ForgeConfig<'cfg> {
         self
     }
 
+    pub fn with_emitter_account_cache(mut self, emitter_account_cache: PathBuf) -> Self {
+        self.emitter_account_cache = Some(emitter_account_cache);
+        self
+    }
+
+    pub fn with_emitter_circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+        self.emitter_circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    pub fn with_block_timeline_dir(mut self, block_timeline_dir: PathBuf) -> Self {
+        self.block_timeline_dir = Some(block_timeline_dir);
+        self
+    }
+
     pub fn number_of_tests(&self) -> usize {
         self.public_usage_tests.len() + self.admin_tests.len() + self.network_tests.len()
     }
@@ -160,6 +211,9 @@ impl<'cfg> Default for ForgeConfig<'cfg> {
             network_tests: &[],
             initial_validator_count: NonZeroUsize::new(1).unwrap(),
             initial_version: InitialVersion::Newest,
+            emitter_account_cache: None,
+            emitter_circuit_breaker: None,
+            block_timeline_dir: None,
         }
     }
 }
@@ -205,12 +259,58 @@ impl<'cfg, F: Factory> Forge<'cfg, F> {
     }
 
     pub fn run(&self) -> Result<()> {
+        self.run_with_reports().map(|_| ())
+    }
+
+    /// Runs the tests like `run`, returning the reports of all network tests which were run.
+    pub fn run_with_reports(&self) -> Result<Vec<TestReport>> {
+        let mut reports = Vec::new();
+        self.run_checking_reports(|r| {
+            reports = r;
+            Ok(())
+        })?;
+        Ok(reports)
+    }
+
+    /// Runs the tests like `run`, then, if all of them passed, hands the reports of all network
+    /// tests which were run to `check_reports`. An error returned by `check_reports`, or by
+    /// setting up the swarm, fails the run and is recorded in the summary like a failed test.
+    pub fn run_checking_reports<C>(&self, check_reports: C) -> Result<()>
+    where
+        C: FnOnce(Vec<TestReport>) -> Result<()>,
+    {
+        let start = Instant::now();
         let test_count = self.filter_tests(self.tests.all_tests()).count();
         let filtered_out = test_count.saturating_sub(self.tests.all_tests().count());
 
         let mut summary = TestSummary::new(test_count, filtered_out);
         summary.write_starting_msg()?;
 
+        match self.run_tests(test_count, &mut summary) {
+            Ok(reports) => {
+                if summary.success() {
+                    if let Err(e) = check_reports(reports) {
+                        summary.handle_step_failure("check_reports", e)?;
+                    }
+                }
+            }
+            Err(e) => summary.handle_step_failure("swarm_setup", e)?,
+        }
+
+        summary.write_summary()?;
+        if let Some(path) = &self.options.summary {
+            summary.write_json_summary(path, self.tests.number_of_tests(), start)?;
+        }
+
+        if summary.success() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Tests Failed"))
+        }
+    }
+
+    fn run_tests(&self, test_count: usize, summary: &mut TestSummary) -> Result<Vec<TestReport>> {
+        let mut reports = Vec::new();
         if test_count > 0 {
             println!(
                 "Starting Swarm with supported versions: {:?}",
@@ -227,6 +327,10 @@ impl<'cfg, F: Factory> Forge<'cfg, F> {
                 &initial_version,
             )?;
 
+            if self.options.check_genesis {
+                swarm.check_genesis_consistency()?;
+            }
+
             // Run PublicUsageTests
             for test in self.filter_tests(self.tests.public_usage_tests.iter()) {
                 let mut public_ctx = PublicUsageContext::new(
@@ -247,10 +351,17 @@ impl<'cfg, F: Factory> Forge<'cfg, F> {
 
             for test in self.filter_tests(self.tests.network_tests.iter()) {
                 let report = TestReport::new();
-                let mut network_ctx =
-                    NetworkContext::new(CoreContext::from_rng(&mut rng), &mut *swarm, report);
+                let mut network_ctx = NetworkContext::new(
+                    CoreContext::from_rng(&mut rng),
+                    &mut *swarm,
+                    report,
+                    self.tests.emitter_account_cache.clone(),
+                    self.tests.emitter_circuit_breaker,
+                    self.tests.block_timeline_dir.clone(),
+                );
                 let result = run_test(|| test.run(&mut network_ctx));
                 summary.handle_result(test.name().to_owned(), result)?;
+                reports.push(network_ctx.report);
             }
 
             io::stdout().flush()?;
@@ -262,13 +373,7 @@ impl<'cfg, F: Factory> Forge<'cfg, F> {
             }
         }
 
-        summary.write_summary()?;
-
-        if summary.success() {
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("Tests Failed"))
-        }
+        Ok(reports)
     }
 
     fn filter_tests<'a, T: Test, I: Iterator<Item = T> + 'a>(
@@ -319,6 +424,8 @@ struct TestSummary {
     filtered_out: usize,
     passed: usize,
     failed: Vec<String>,
+    /// Steps of the run other than tests which failed, e.g. launching the swarm
+    failed_steps: Vec<String>,
 }
 
 impl TestSummary {
@@ -329,6 +436,7 @@ impl TestSummary {
             filtered_out,
             passed: 0,
             failed: Vec::new(),
+            failed_steps: Vec::new(),
         }
     }
 
@@ -355,6 +463,15 @@ impl TestSummary {
         Ok(())
     }
 
+    fn handle_step_failure(&mut self, step: &str, error: anyhow::Error) -> io::Result<()> {
+        write!(self.stdout, "step {} ... ", step)?;
+        self.failed_steps.push(step.to_owned());
+        self.write_failed()?;
+        writeln!(self.stdout)?;
+        writeln!(self.stdout, "Error: {:?}", error)?;
+        Ok(())
+    }
+
     fn write_ok(&mut self) -> io::Result<()> {
         self.stdout
             .set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
@@ -382,18 +499,18 @@ impl TestSummary {
     }
 
     fn write_summary(&mut self) -> io::Result<()> {
-        // Print out the failing tests
-        if !self.failed.is_empty() {
+        // Print out the failing tests and steps
+        if !self.success() {
             writeln!(self.stdout)?;
             writeln!(self.stdout, "failures:")?;
-            for name in &self.failed {
+            for name in self.failed.iter().chain(&self.failed_steps) {
                 writeln!(self.stdout, "    {}", name)?;
             }
         }
 
         writeln!(self.stdout)?;
         write!(self.stdout, "test result: ")?;
-        if self.failed.is_empty() {
+        if self.success() {
             self.write_ok()?;
         } else {
             self.write_failed()?;
@@ -409,7 +526,86 @@ impl TestSummary {
         Ok(())
     }
 
+    /// Writes a machine-readable summary to `path`. All tests of the config which weren't run,
+    /// because they were filtered out or ignored, or because the run failed before reaching them,
+    /// are counted as skipped. Failed steps count as failures, so that a failed run never reports
+    /// zero failures.
+    fn write_json_summary(&self, path: &Path, all_tests: usize, start: Instant) -> Result<()> {
+        let summary = JsonSummary {
+            total: all_tests,
+            passed: self.passed,
+            failed: self.failed.len() + self.failed_steps.len(),
+            skipped: all_tests.saturating_sub(self.passed + self.failed.len()),
+            failed_tests: &self.failed,
+            failed_steps: &self.failed_steps,
+            duration_secs: start.elapsed().as_secs_f64(),
+        };
+        fs::write(path, serde_json::to_string_pretty(&summary)?)?;
+        Ok(())
+    }
+
     fn success(&self) -> bool {
-        self.failed.is_empty()
+        self.failed.is_empty() && self.failed_steps.is_empty()
+    }
+}
+
+#[derive(Serialize)]
+struct JsonSummary<'a> {
+    total: usize,
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    failed_tests: &'a [String],
+    failed_steps: &'a [String],
+    duration_secs: f64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn json_summary(summary: &TestSummary, all_tests: usize) -> serde_json::Value {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        summary
+            .write_json_summary(file.path(), all_tests, Instant::now())
+            .unwrap();
+        serde_json::from_slice(&fs::read(file.path()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_json_summary_counts_failed_steps() {
+        let mut summary = TestSummary::new(3, 0);
+        summary
+            .handle_result("passing".to_owned(), TestResult::Ok)
+            .unwrap();
+        summary
+            .handle_result("failing".to_owned(), TestResult::Failed)
+            .unwrap();
+        let json = json_summary(&summary, 4);
+        assert_eq!(json["passed"], 1);
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["skipped"], 2);
+
+        summary
+            .handle_step_failure("check_reports", anyhow::anyhow!("regressed"))
+            .unwrap();
+        assert!(!summary.success());
+        let json = json_summary(&summary, 4);
+        assert_eq!(json["failed"], 2);
+        assert_eq!(json["skipped"], 2);
+        assert_eq!(json["failed_tests"], serde_json::json!(["failing"]));
+        assert_eq!(json["failed_steps"], serde_json::json!(["check_reports"]));
+    }
+
+    #[test]
+    fn test_json_summary_of_failed_setup() {
+        let mut summary = TestSummary::new(2, 0);
+        summary
+            .handle_step_failure("swarm_setup", anyhow::anyhow!("no nodes"))
+            .unwrap();
+        let json = json_summary(&summary, 2);
+        assert_eq!(json["passed"], 0);
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["skipped"], 2);
     }
 }
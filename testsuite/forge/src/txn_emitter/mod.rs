@@ -2,29 +2,46 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::ChainInfo;
-use anyhow::{anyhow, format_err, Context, Result};
+use anyhow::{anyhow, ensure, format_err, Context, Result};
 use diem_logger::*;
 use diem_sdk::{
-    client::{views::AmountView, Client as JsonRpcClient, MethodRequest},
-    crypto::hash::CryptoHash,
-    move_types::account_address::AccountAddress,
+    client::{
+        views::{AccountView, AmountView},
+        Client as JsonRpcClient, MethodRequest,
+    },
+    crypto::{
+        ed25519::Ed25519PrivateKey,
+        hash::{CryptoHash, HashValue},
+        ValidCryptoMaterialStringExt,
+    },
+    move_types::{
+        account_address::AccountAddress,
+        identifier::Identifier,
+        language_storage::{ModuleId, TypeTag},
+    },
     transaction_builder::{Currency, TransactionFactory},
     types::{
-        account_config::XUS_NAME,
-        transaction::{authenticator::AuthenticationKey, SignedTransaction, Transaction},
+        transaction::{
+            authenticator::AuthenticationKey, Script, ScriptFunction, SignedTransaction,
+            Transaction, TransactionPayload,
+        },
         LocalAccount,
     },
 };
 use futures::future::{try_join_all, FutureExt};
 use itertools::zip;
+use move_binary_format::file_format::{empty_script, Signature, SignatureIndex, SignatureToken};
 use rand::{
     seq::{IteratorRandom, SliceRandom},
     Rng,
 };
 use rand_core::SeedableRng;
+use serde::{Deserialize, Serialize};
 use std::{
     cmp::{max, min},
     collections::HashSet,
+    fmt, fs,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
@@ -61,6 +78,8 @@ pub struct EmitJobRequest {
     pub accounts_per_client: usize,
     pub workers_per_endpoint: Option<usize>,
     pub thread_params: EmitThreadParams,
+    pub circuit_breaker: Option<CircuitBreaker>,
+    pub transaction_type: TransactionType,
 }
 
 impl EmitJobRequest {
@@ -70,10 +89,130 @@ impl EmitJobRequest {
             accounts_per_client: 15,
             workers_per_endpoint: None,
             thread_params: EmitThreadParams::default(),
+            circuit_breaker: None,
+            transaction_type: TransactionType::p2p(),
+        }
+    }
+}
+
+/// The kind of transactions which are emitted.
+#[derive(Clone)]
+pub enum TransactionType {
+    /// Transfers between the accounts of the emitter, each in one of the given currencies picked
+    /// at random, so that the traffic is split evenly among them.
+    P2P(Vec<Currency>),
+    /// Calls of a script function of a published module.
+    ModuleCall(ModuleCall),
+    /// Multi-agent transactions, each signed by its sender and the given number of secondary
+    /// signers, which are picked among the other accounts of the same worker. The transactions
+    /// run a script which does nothing, so that the cost of verifying the signatures dominates.
+    MultiAgent { num_secondary_signers: usize },
+}
+
+impl TransactionType {
+    /// Transfers of XUS between the accounts of the emitter.
+    pub fn p2p() -> Self {
+        TransactionType::P2P(vec![Currency::XUS])
+    }
+
+    /// The currencies the accounts of the emitter need to hold to send these transactions. The
+    /// first one is the currency in which the accounts are created.
+    fn currencies(&self) -> Vec<Currency> {
+        match self {
+            TransactionType::P2P(currencies) => currencies.clone(),
+            TransactionType::ModuleCall(_) | TransactionType::MultiAgent { .. } => {
+                vec![Currency::XUS]
+            }
+        }
+    }
+}
+
+/// A call of the script function `function` of `module`. The arguments of each transaction are
+/// produced by `args_generator`, as BCS-serialized values, so that they can vary across
+/// transactions.
+#[derive(Clone)]
+pub struct ModuleCall {
+    pub module: ModuleId,
+    pub function: Identifier,
+    pub ty_args: Vec<TypeTag>,
+    pub args_generator: Arc<dyn Fn(&mut ::rand::rngs::StdRng) -> Vec<Vec<u8>> + Send + Sync>,
+}
+
+/// Stops emitting transactions early if the network stops making progress. The job is aborted
+/// once the ratio of committed to submitted transactions stays below `min_success_rate` for
+/// `sustained_windows` consecutive windows of length `window`.
+#[derive(Clone, Copy, Debug)]
+pub struct CircuitBreaker {
+    pub min_success_rate: f64,
+    pub window: Duration,
+    pub sustained_windows: usize,
+}
+
+impl CircuitBreaker {
+    pub fn new(min_success_rate: f64, window: Duration, sustained_windows: usize) -> Self {
+        Self {
+            min_success_rate,
+            window,
+            sustained_windows,
+        }
+    }
+
+    /// Watches `job` for `duration`, returning the reason if the circuit breaker tripped.
+    async fn watch(&self, job: &EmitJob, duration: Duration) -> Option<String> {
+        let deadline = Instant::now() + duration;
+        let mut previous = job.stats.accumulate();
+        let mut failed_windows = 0;
+        while Instant::now() < deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            time::sleep(min(self.window, remaining)).await;
+            let current = job.stats.accumulate();
+            let submitted = current.submitted - previous.submitted;
+            let committed = current.committed - previous.committed;
+            // A window without any submissions means the emitter is stuck, which counts as failed
+            let success_rate = if submitted == 0 {
+                0.0
+            } else {
+                committed as f64 / submitted as f64
+            };
+            if success_rate < self.min_success_rate {
+                failed_windows += 1;
+                if failed_windows >= self.sustained_windows {
+                    return Some(format!(
+                        "success rate stayed below {} for {} windows of {}s",
+                        self.min_success_rate,
+                        failed_windows,
+                        self.window.as_secs()
+                    ));
+                }
+            } else {
+                failed_windows = 0;
+            }
+            previous = current;
         }
+        None
+    }
+}
+
+/// Error returned when an emit job was aborted by its `CircuitBreaker`. Contains the stats of
+/// the transactions emitted until the job was aborted.
+#[derive(Debug)]
+pub struct EmitAborted {
+    pub stats: TxnStats,
+    pub reason: String,
+}
+
+impl fmt::Display for EmitAborted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Transaction emission aborted after {} submitted and {} committed txns: {}",
+            self.stats.submitted, self.stats.committed, self.reason
+        )
     }
 }
 
+impl std::error::Error for EmitAborted {}
+
 #[derive(Debug, Default)]
 pub struct TxnStats {
     pub submitted: u64,
@@ -120,6 +259,7 @@ struct SubmissionWorker {
     stats: Arc<StatsAccumulator>,
     txn_factory: TransactionFactory,
     rng: ::rand::rngs::StdRng,
+    transaction_type: TransactionType,
 }
 
 impl SubmissionWorker {
@@ -193,6 +333,12 @@ impl SubmissionWorker {
     }
 
     fn gen_requests(&mut self) -> Vec<SignedTransaction> {
+        if let TransactionType::MultiAgent {
+            num_secondary_signers,
+        } = self.transaction_type
+        {
+            return self.gen_multi_agent_requests(num_secondary_signers);
+        }
         let batch_size = max(MAX_TXN_BATCH_SIZE, self.accounts.len());
         let accounts = self
             .accounts
@@ -200,16 +346,70 @@ impl SubmissionWorker {
             .choose_multiple(&mut self.rng, batch_size);
         let mut requests = Vec::with_capacity(accounts.len());
         for sender in accounts {
-            let receiver = self
-                .all_addresses
-                .choose(&mut self.rng)
-                .expect("all_addresses can't be empty");
-            let request =
-                gen_transfer_txn_request(sender, receiver, SEND_AMOUNT, &self.txn_factory);
+            let request = match &self.transaction_type {
+                TransactionType::P2P(currencies) => {
+                    let receiver = self
+                        .all_addresses
+                        .choose(&mut self.rng)
+                        .expect("all_addresses can't be empty");
+                    let currency = currencies
+                        .choose(&mut self.rng)
+                        .expect("currencies can't be empty");
+                    gen_transfer_txn_request(
+                        sender,
+                        receiver,
+                        *currency,
+                        SEND_AMOUNT,
+                        &self.txn_factory,
+                    )
+                }
+                TransactionType::ModuleCall(call) => {
+                    let args = (call.args_generator)(&mut self.rng);
+                    gen_module_call_txn_request(sender, call, args, &self.txn_factory)
+                }
+                TransactionType::MultiAgent { .. } => unreachable!(),
+            };
             requests.push(request);
         }
         requests
     }
+
+    fn gen_multi_agent_requests(&mut self, num_secondary_signers: usize) -> Vec<SignedTransaction> {
+        let script = gen_multi_agent_script(num_secondary_signers);
+        let batch_size = max(MAX_TXN_BATCH_SIZE, self.accounts.len());
+        let senders = (0..self.accounts.len()).choose_multiple(&mut self.rng, batch_size);
+        let mut requests = Vec::with_capacity(senders.len());
+        for sender in senders {
+            let secondary_signers = (0..self.accounts.len())
+                .filter(|i| *i != sender)
+                .choose_multiple(&mut self.rng, num_secondary_signers);
+            requests.push(gen_multi_agent_txn_request(
+                &mut self.accounts,
+                sender,
+                &secondary_signers,
+                script.clone(),
+                &self.txn_factory,
+            ));
+        }
+        requests
+    }
+}
+
+/// An account created by the emitter, as persisted in the account cache.
+#[derive(Serialize, Deserialize)]
+struct CachedAccount {
+    address: AccountAddress,
+    private_key: String,
+    sequence_number: u64,
+}
+
+/// The account cache, together with the chain the accounts were created on. The genesis
+/// transaction tells apart ledgers which have been wiped and recreated with the same chain id.
+#[derive(Serialize, Deserialize)]
+struct AccountCache {
+    chain_id: u8,
+    genesis_hash: HashValue,
+    accounts: Vec<CachedAccount>,
 }
 
 #[derive(Debug)]
@@ -219,6 +419,7 @@ pub struct TxnEmitter<'t> {
     chain_info: ChainInfo<'t>,
     client: JsonRpcClient,
     rng: ::rand::rngs::StdRng,
+    account_cache: Option<PathBuf>,
 }
 
 impl<'t> TxnEmitter<'t> {
@@ -231,9 +432,17 @@ impl<'t> TxnEmitter<'t> {
             chain_info,
             client,
             rng,
+            account_cache: None,
         }
     }
 
+    /// Persists the accounts created by this emitter to `path` when a job is stopped, and reuses
+    /// the accounts found there when the first job is started, instead of creating new ones.
+    pub fn with_account_cache(mut self, path: PathBuf) -> Self {
+        self.account_cache = Some(path);
+        self
+    }
+
     pub fn rng(&mut self) -> &mut ::rand::rngs::StdRng {
         &mut self.rng
     }
@@ -242,27 +451,38 @@ impl<'t> TxnEmitter<'t> {
         ::rand::rngs::StdRng::from_rng(self.rng()).unwrap()
     }
 
-    pub async fn get_money_source(&mut self, coins_total: u64) -> Result<&mut LocalAccount> {
+    pub async fn get_money_source(
+        &mut self,
+        coins_total: u64,
+        currencies: &[Currency],
+    ) -> Result<&mut LocalAccount> {
         let client = self.client.clone();
         println!("Creating and minting faucet account");
         let mut faucet_account = &mut self.chain_info.designated_dealer_account;
-        let mint_txn = gen_transfer_txn_request(
-            faucet_account,
-            &faucet_account.address(),
-            coins_total,
-            &self.txn_factory,
-        );
-        execute_and_wait_transactions(&client, &mut faucet_account, vec![mint_txn])
+        let mut mint_txns = vec![];
+        for currency in currencies {
+            let address = faucet_account.address();
+            mint_txns.push(gen_transfer_txn_request(
+                faucet_account,
+                &address,
+                *currency,
+                coins_total,
+                &self.txn_factory,
+            ));
+        }
+        execute_and_wait_transactions(&client, &mut faucet_account, mint_txns)
             .await
             .map_err(|e| format_err!("Failed to mint into faucet account: {}", e))?;
         let balance = retrieve_account_balance(&client, faucet_account.address()).await?;
         for b in balance {
-            if b.currency.eq(XUS_NAME) {
+            if currencies
+                .iter()
+                .any(|currency| b.currency.eq(currency.as_str()))
+            {
                 println!(
-                    "DD account current balances are {}, requested {} coins",
-                    b.amount, coins_total
+                    "DD account current balance is {} {}, requested {} coins",
+                    b.amount, b.currency, coins_total
                 );
-                break;
             }
         }
         Ok(faucet_account)
@@ -272,6 +492,7 @@ impl<'t> TxnEmitter<'t> {
         &mut self,
         json_rpc_clients: &[JsonRpcClient],
         seed_account_num: usize,
+        currencies: &[Currency],
     ) -> Result<Vec<LocalAccount>> {
         info!("Creating and minting seeds accounts");
         let mut i = 0;
@@ -288,6 +509,7 @@ impl<'t> TxnEmitter<'t> {
                     create_parent_vasp_request(
                         creation_account,
                         account.authentication_key(),
+                        currencies,
                         txn_factory,
                     )
                 })
@@ -324,28 +546,36 @@ impl<'t> TxnEmitter<'t> {
         let coins_total = coins_per_account * num_accounts as u64;
         let txn_factory = self.txn_factory.clone();
         let client = self.pick_mint_client(&req.json_rpc_clients);
+        let currencies = req.transaction_type.currencies();
 
         // Create seed accounts with which we can create actual accounts concurrently
         let seed_accounts = self
-            .get_seed_accounts(&req.json_rpc_clients, req.json_rpc_clients.len())
+            .get_seed_accounts(
+                &req.json_rpc_clients,
+                req.json_rpc_clients.len(),
+                &currencies,
+            )
             .await?;
-        let rng = self.from_rng();
-        let faucet_account = self.get_money_source(coins_total).await?;
+        let mut rng = self.from_rng();
+        let faucet_account = self.get_money_source(coins_total, &currencies).await?;
         let actual_num_seed_accounts = seed_accounts.len();
         let num_new_child_accounts =
             (num_accounts + actual_num_seed_accounts - 1) / actual_num_seed_accounts;
         let coins_per_seed_account = coins_per_account * num_new_child_accounts as u64;
-        mint_to_new_accounts(
-            faucet_account,
-            &seed_accounts,
-            coins_per_seed_account as u64,
-            100,
-            client.clone(),
-            &txn_factory,
-            rng,
-        )
-        .await
-        .map_err(|e| format_err!("Failed to mint seed_accounts: {}", e))?;
+        for currency in &currencies {
+            mint_to_new_accounts(
+                faucet_account,
+                &seed_accounts,
+                *currency,
+                coins_per_seed_account as u64,
+                100,
+                client.clone(),
+                &txn_factory,
+                &mut rng,
+            )
+            .await
+            .map_err(|e| format_err!("Failed to mint seed_accounts: {}", e))?;
+        }
         println!("Completed minting seed accounts");
         println!("Minting additional {} accounts", num_accounts);
 
@@ -360,6 +590,7 @@ impl<'t> TxnEmitter<'t> {
                 create_new_accounts(
                     seed_account,
                     num_new_child_accounts,
+                    &currencies,
                     coins_per_account,
                     20,
                     cur_client,
@@ -386,6 +617,20 @@ impl<'t> TxnEmitter<'t> {
     }
 
     pub async fn start_job(&mut self, req: EmitJobRequest) -> Result<EmitJob> {
+        let currencies = req.transaction_type.currencies();
+        ensure!(
+            !currencies.is_empty(),
+            "At least one currency is needed to emit transfers"
+        );
+        // The accounts of the emitter are funded by the designated dealer, which e.g. holds no
+        // XDX, as XDX cannot be minted
+        let dd_balances = retrieve_account_balance(
+            &self.client,
+            self.chain_info.designated_dealer_account.address(),
+        )
+        .await?;
+        check_balances(&dd_balances, &currencies, 1)
+            .context("The designated dealer cannot fund the requested currencies")?;
         let workers_per_endpoint = match req.workers_per_endpoint {
             Some(x) => x,
             None => {
@@ -402,11 +647,29 @@ impl<'t> TxnEmitter<'t> {
             "Will use {} workers per endpoint with total {} endpoint clients",
             workers_per_endpoint, num_clients
         );
+        if let TransactionType::MultiAgent {
+            num_secondary_signers,
+        } = req.transaction_type
+        {
+            ensure!(
+                req.accounts_per_client > num_secondary_signers,
+                "Multi-agent transactions with {} secondary signers require more than {} \
+                 accounts per client",
+                num_secondary_signers,
+                num_secondary_signers
+            );
+        }
         let num_accounts = req.accounts_per_client * num_clients;
         println!(
             "Will create {} accounts_per_client with total {} accounts",
             req.accounts_per_client, num_accounts
         );
+        if self.accounts.is_empty() {
+            if let Some(path) = self.account_cache.clone() {
+                self.load_account_cache(&path, &req.json_rpc_clients, &currencies)
+                    .await?;
+            }
+        }
         self.mint_accounts(&req, num_accounts).await?;
         let all_accounts = self.accounts.split_off(self.accounts.len() - num_accounts);
         let mut workers = vec![];
@@ -432,6 +695,7 @@ impl<'t> TxnEmitter<'t> {
                     stats,
                     txn_factory: self.txn_factory.clone(),
                     rng: self.from_rng(),
+                    transaction_type: req.transaction_type.clone(),
                 };
                 let join_handle = tokio_handle.spawn(worker.run().boxed());
                 workers.push(Worker { join_handle });
@@ -454,19 +718,102 @@ impl<'t> TxnEmitter<'t> {
                 .expect("TxnEmitter worker thread failed");
             self.accounts.append(&mut accounts);
         }
+        if let Some(path) = &self.account_cache {
+            if let Err(e) = self.save_account_cache(path).await {
+                warn!("Failed to save accounts to {}: {:?}", path.display(), e);
+            }
+        }
         job.stats.accumulate()
     }
 
+    /// Loads the accounts cached at `path` from a previous run, if any, and re-syncs their
+    /// sequence numbers from the chain. Accounts which no longer exist on chain, or which cannot
+    /// send a transfer in each of `currencies`, are dropped.
+    /// If the cache cannot be parsed, or was written for a different chain or for a ledger
+    /// which has been wiped since, it is ignored and new accounts are created, which replace the
+    /// cache when the job is stopped.
+    async fn load_account_cache(
+        &mut self,
+        path: &Path,
+        json_rpc_clients: &[JsonRpcClient],
+        currencies: &[Currency],
+    ) -> Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let cache: AccountCache = match serde_json::from_slice(&fs::read(path)?) {
+            Ok(cache) => cache,
+            Err(e) => {
+                warn!(
+                    "Ignoring account cache {}: failed to parse it, e.g. because it was written \
+                     in an older format: {}",
+                    path.display(),
+                    e
+                );
+                return Ok(());
+            }
+        };
+        let client = self.pick_mint_client(json_rpc_clients).clone();
+        let chain_id = self.chain_info.chain_id().id();
+        let genesis_hash = query_genesis_hash(&client).await?;
+        if cache.chain_id != chain_id || cache.genesis_hash != genesis_hash {
+            warn!(
+                "Ignoring account cache {}: it was written for chain id {} with genesis {}, \
+                 but the chain has id {} and genesis {}",
+                path.display(),
+                cache.chain_id,
+                cache.genesis_hash,
+                chain_id,
+                genesis_hash
+            );
+            return Ok(());
+        }
+        let cached = cache.accounts;
+        let num_cached = cached.len();
+        let addresses = cached.iter().map(|a| a.address).collect::<Vec<_>>();
+        let account_views = query_optional_accounts(&client, &addresses).await?;
+        self.accounts
+            .extend(restore_cached_accounts(cached, account_views, currencies)?);
+        println!(
+            "Loaded {} of {} cached accounts from {}",
+            self.accounts.len(),
+            num_cached,
+            path.display()
+        );
+        Ok(())
+    }
+
+    /// Saves all accounts currently owned by this emitter to `path`.
+    async fn save_account_cache(&self, path: &Path) -> Result<()> {
+        let cache = AccountCache {
+            chain_id: self.chain_info.chain_id().id(),
+            genesis_hash: query_genesis_hash(&self.client).await?,
+            accounts: cache_accounts(&self.accounts)?,
+        };
+        fs::write(path, serde_json::to_vec(&cache)?)?;
+        Ok(())
+    }
+
     pub async fn emit_txn_for(
         &mut self,
         duration: Duration,
         emit_job_request: EmitJobRequest,
     ) -> Result<TxnStats> {
+        let circuit_breaker = emit_job_request.circuit_breaker;
         let job = self.start_job(emit_job_request).await?;
         println!("starting emitting txns for {} secs", duration.as_secs());
-        tokio::time::sleep(duration).await;
+        let tripped = match circuit_breaker {
+            Some(circuit_breaker) => circuit_breaker.watch(&job, duration).await,
+            None => {
+                tokio::time::sleep(duration).await;
+                None
+            }
+        };
         let stats = self.stop_job(job).await;
-        Ok(stats)
+        match tripped {
+            Some(reason) => Err(EmitAborted { stats, reason }.into()),
+            None => Ok(stats),
+        }
     }
 
     fn pick_mint_client<'a>(&mut self, clients: &'a [JsonRpcClient]) -> &'a JsonRpcClient {
@@ -476,6 +823,61 @@ impl<'t> TxnEmitter<'t> {
     }
 }
 
+fn cache_accounts(accounts: &[LocalAccount]) -> Result<Vec<CachedAccount>> {
+    accounts
+        .iter()
+        .map(|account| {
+            Ok(CachedAccount {
+                address: account.address(),
+                private_key: account.private_key().to_encoded_string()?,
+                sequence_number: account.sequence_number(),
+            })
+        })
+        .collect()
+}
+
+/// Restores the `cached` accounts whose on-chain state in `account_views` shows they still exist
+/// and can send a transfer in each of `currencies`, with their sequence numbers taken from chain.
+fn restore_cached_accounts(
+    cached: Vec<CachedAccount>,
+    account_views: Vec<Option<AccountView>>,
+    currencies: &[Currency],
+) -> Result<Vec<LocalAccount>> {
+    let mut accounts = vec![];
+    for (account, account_view) in zip(cached, account_views) {
+        match account_view {
+            Some(view) if check_balances(&view.balances, currencies, SEND_AMOUNT).is_ok() => {
+                let private_key = Ed25519PrivateKey::from_encoded_string(&account.private_key)?;
+                accounts.push(LocalAccount::new(
+                    account.address,
+                    private_key,
+                    view.sequence_number,
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(accounts)
+}
+
+/// Checks that `balances` hold at least `amount` coins of each of `currencies`.
+fn check_balances(balances: &[AmountView], currencies: &[Currency], amount: u64) -> Result<()> {
+    for currency in currencies {
+        let balance = balances
+            .iter()
+            .find(|b| b.currency == currency.as_str())
+            .map_or(0, |b| b.amount);
+        ensure!(
+            balance >= amount,
+            "Balance of {} {} is below the required {}",
+            balance,
+            currency.as_str(),
+            amount
+        );
+    }
+    Ok(())
+}
+
 async fn retrieve_account_balance(
     client: &JsonRpcClient,
     address: AccountAddress,
@@ -635,6 +1037,43 @@ pub async fn query_sequence_numbers(
     client: &JsonRpcClient,
     addresses: &[AccountAddress],
 ) -> Result<Vec<u64>> {
+    query_optional_sequence_numbers(client, addresses)
+        .await?
+        .into_iter()
+        .map(|seq| seq.ok_or_else(|| format_err!("account does not exist")))
+        .collect()
+}
+
+/// Returns the hash of the genesis transaction of the ledger behind `client`
+async fn query_genesis_hash(client: &JsonRpcClient) -> Result<HashValue> {
+    let genesis = client
+        .get_transactions(0, 1, false)
+        .await
+        .map_err(|e| format_err!("[{:?}] get_transactions failed: {:?} ", client, e))?
+        .into_inner()
+        .into_iter()
+        .next()
+        .ok_or_else(|| format_err!("[{:?}] genesis transaction not found", client))?;
+    Ok(genesis.hash)
+}
+
+/// Queries the sequence numbers of `addresses`, returning `None` for accounts which do not exist.
+async fn query_optional_sequence_numbers(
+    client: &JsonRpcClient,
+    addresses: &[AccountAddress],
+) -> Result<Vec<Option<u64>>> {
+    Ok(query_optional_accounts(client, addresses)
+        .await?
+        .into_iter()
+        .map(|account| account.map(|account| account.sequence_number))
+        .collect())
+}
+
+/// Queries the accounts at `addresses`, returning `None` for accounts which do not exist.
+async fn query_optional_accounts(
+    client: &JsonRpcClient,
+    addresses: &[AccountAddress],
+) -> Result<Vec<Option<AccountView>>> {
     let mut result = vec![];
     for addresses_batch in addresses.chunks(20) {
         let resp = client
@@ -651,21 +1090,17 @@ pub async fn query_sequence_numbers(
             .collect::<Result<Vec<_>>>()
             .map_err(|e| format_err!("[{:?}] get_accounts failed: {:?} ", client, e))?;
 
-        for item in resp.into_iter() {
-            result.push(
-                item.ok_or_else(|| format_err!("account does not exist"))?
-                    .sequence_number,
-            );
-        }
+        result.extend(resp);
     }
     Ok(result)
 }
 
-/// Create `num_new_accounts` by transferring diem from `source_account`. Return Vec of created
-/// accounts
+/// Create `num_new_accounts` by transferring diem from `source_account`, in each of `currencies`.
+/// Return Vec of created accounts
 async fn create_new_accounts<R>(
     mut source_account: LocalAccount,
     num_new_accounts: usize,
+    currencies: &[Currency],
     diem_per_new_account: u64,
     max_num_accounts_per_batch: u64,
     client: JsonRpcClient,
@@ -688,24 +1123,38 @@ where
             .iter()
             .map(|account| {
                 source_account.sign_with_transaction_builder(txn_factory.create_child_vasp_account(
-                    Currency::XUS,
+                    currencies[0],
                     account.authentication_key(),
-                    false,
+                    currencies.len() > 1,
                     diem_per_new_account,
                 ))
             })
             .collect();
         execute_and_wait_transactions(&client, &mut source_account, requests).await?;
+        for currency in &currencies[1..] {
+            mint_to_new_accounts(
+                &mut source_account,
+                &batch,
+                *currency,
+                diem_per_new_account,
+                max_num_accounts_per_batch,
+                client.clone(),
+                txn_factory,
+                &mut rng,
+            )
+            .await?;
+        }
         i += batch.len();
         accounts.append(&mut batch);
     }
     Ok(accounts)
 }
 
-/// Mint `diem_per_new_account` from `minting_account` to each account in `accounts`.
+/// Mint `diem_per_new_account` of `currency` from `minting_account` to each account in `accounts`.
 async fn mint_to_new_accounts<R>(
     minting_account: &mut LocalAccount,
     accounts: &[LocalAccount],
+    currency: Currency,
     diem_per_new_account: u64,
     max_num_accounts_per_batch: u64,
     client: JsonRpcClient,
@@ -731,6 +1180,7 @@ where
                 gen_transfer_txn_request(
                     minting_account,
                     &account.address(),
+                    currency,
                     diem_per_new_account,
                     txn_factory,
                 )
@@ -743,17 +1193,19 @@ where
     Ok(())
 }
 
+/// Creates a parent VASP holding all of `currencies`, the first of which is its main currency.
 pub fn create_parent_vasp_request(
     creation_account: &mut LocalAccount,
     account_auth_key: AuthenticationKey,
+    currencies: &[Currency],
     txn_factory: &TransactionFactory,
 ) -> SignedTransaction {
     creation_account.sign_with_transaction_builder(txn_factory.create_parent_vasp_account(
-        Currency::XUS,
+        currencies[0],
         0,
         account_auth_key,
         "",
-        false,
+        currencies.len() > 1,
     ))
 }
 
@@ -769,14 +1221,78 @@ where
 pub fn gen_transfer_txn_request(
     sender: &mut LocalAccount,
     receiver: &AccountAddress,
+    currency: Currency,
     num_coins: u64,
     txn_factory: &TransactionFactory,
 ) -> SignedTransaction {
-    sender.sign_with_transaction_builder(txn_factory.peer_to_peer(
-        Currency::XUS,
-        *receiver,
-        num_coins,
-    ))
+    sender.sign_with_transaction_builder(txn_factory.peer_to_peer(currency, *receiver, num_coins))
+}
+
+pub fn gen_module_call_txn_request(
+    sender: &mut LocalAccount,
+    call: &ModuleCall,
+    args: Vec<Vec<u8>>,
+    txn_factory: &TransactionFactory,
+) -> SignedTransaction {
+    sender.sign_with_transaction_builder(txn_factory.payload(TransactionPayload::ScriptFunction(
+        ScriptFunction::new(
+            call.module.clone(),
+            call.function.clone(),
+            call.ty_args.clone(),
+            args,
+        ),
+    )))
+}
+
+/// Signs a multi-agent transaction running `script`, sent by `accounts[sender]` and co-signed by
+/// the accounts at the `secondary_signers` indices. Only the sequence number of the sender is
+/// consumed.
+pub fn gen_multi_agent_txn_request(
+    accounts: &mut [LocalAccount],
+    sender: usize,
+    secondary_signers: &[usize],
+    script: Script,
+    txn_factory: &TransactionFactory,
+) -> SignedTransaction {
+    let txn = {
+        let sender = &accounts[sender];
+        txn_factory
+            .script(script)
+            .sender(sender.address())
+            .sequence_number(sender.sequence_number())
+            .build()
+            .sign_multi_agent(
+                sender.private_key(),
+                secondary_signers
+                    .iter()
+                    .map(|i| accounts[*i].address())
+                    .collect(),
+                secondary_signers
+                    .iter()
+                    .map(|i| accounts[*i].private_key())
+                    .collect(),
+            )
+            .expect("Signing multi agent txn failed")
+            .into_inner()
+    };
+    *accounts[sender].sequence_number_mut() += 1;
+    txn
+}
+
+/// Returns a script which takes a signer for the sender and each of the secondary signers, and
+/// does nothing.
+fn gen_multi_agent_script(num_secondary_signers: usize) -> Script {
+    let mut script = empty_script();
+    script.signatures.push(Signature(vec![
+        SignatureToken::Signer;
+        num_secondary_signers + 1
+    ]));
+    script.parameters = SignatureIndex(1);
+    let mut code = vec![];
+    script
+        .serialize(&mut code)
+        .expect("Serializing the multi-agent script failed");
+    Script::new(code, vec![], vec![])
 }
 
 impl StatsAccumulator {
@@ -806,3 +1322,194 @@ impl TxnStats {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use diem_sdk::{
+        client::views::{AccountRoleView, BytesView},
+        move_types::language_storage::CORE_CODE_ADDRESS,
+        types::{chain_id::ChainId, event::EventKey},
+    };
+    use move_binary_format::file_format::CompiledScript;
+
+    fn amount(amount: u64, currency: Currency) -> AmountView {
+        AmountView {
+            amount,
+            currency: currency.as_str().to_string(),
+        }
+    }
+
+    fn account_view(
+        address: AccountAddress,
+        sequence_number: u64,
+        balances: Vec<AmountView>,
+    ) -> AccountView {
+        AccountView {
+            address,
+            balances,
+            sequence_number,
+            authentication_key: BytesView::from(vec![]),
+            sent_events_key: EventKey::new_from_address(&address, 0),
+            received_events_key: EventKey::new_from_address(&address, 1),
+            delegated_key_rotation_capability: false,
+            delegated_withdrawal_capability: false,
+            is_frozen: false,
+            role: AccountRoleView::Unknown,
+            version: None,
+        }
+    }
+
+    #[test]
+    fn test_account_cache_round_trip() {
+        let mut rng = ::rand::rngs::StdRng::seed_from_u64(0);
+        let mut accounts = gen_random_accounts(3, &mut rng);
+        for (i, account) in accounts.iter_mut().enumerate() {
+            *account.sequence_number_mut() = i as u64;
+        }
+        let cache = AccountCache {
+            chain_id: 4,
+            genesis_hash: HashValue::random_with_rng(&mut rng),
+            accounts: cache_accounts(&accounts).unwrap(),
+        };
+        let cache: AccountCache =
+            serde_json::from_slice(&serde_json::to_vec(&cache).unwrap()).unwrap();
+        assert_eq!(cache.chain_id, 4);
+
+        // The cached sequence numbers are stale, the ones on chain are used instead
+        let account_views = accounts
+            .iter()
+            .map(|a| {
+                Some(account_view(
+                    a.address(),
+                    10,
+                    vec![amount(1, Currency::XUS)],
+                ))
+            })
+            .collect();
+        let restored =
+            restore_cached_accounts(cache.accounts, account_views, &[Currency::XUS]).unwrap();
+        assert_eq!(restored.len(), accounts.len());
+        for (account, restored) in zip(&accounts, &restored) {
+            assert_eq!(restored.address(), account.address());
+            assert_eq!(restored.public_key(), account.public_key());
+            assert_eq!(restored.sequence_number(), 10);
+        }
+    }
+
+    #[test]
+    fn test_restore_cached_accounts() {
+        let mut rng = ::rand::rngs::StdRng::seed_from_u64(0);
+        let accounts = gen_random_accounts(4, &mut rng);
+        let account_views = vec![
+            Some(account_view(
+                accounts[0].address(),
+                5,
+                vec![amount(1, Currency::XUS), amount(1, Currency::XDX)],
+            )),
+            // No longer on chain, e.g. the cache outlived a ledger with the same genesis
+            None,
+            // Can't afford a transfer
+            Some(account_view(
+                accounts[2].address(),
+                5,
+                vec![amount(0, Currency::XUS), amount(1, Currency::XDX)],
+            )),
+            // Doesn't hold one of the currencies
+            Some(account_view(
+                accounts[3].address(),
+                5,
+                vec![amount(1, Currency::XUS)],
+            )),
+        ];
+        let restored = restore_cached_accounts(
+            cache_accounts(&accounts).unwrap(),
+            account_views,
+            &[Currency::XUS, Currency::XDX],
+        )
+        .unwrap();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].address(), accounts[0].address());
+        assert_eq!(restored[0].sequence_number(), 5);
+    }
+
+    #[test]
+    fn test_gen_module_call_txn_request() {
+        let mut rng = ::rand::rngs::StdRng::seed_from_u64(0);
+        let mut sender = LocalAccount::generate(&mut rng);
+        let call = ModuleCall {
+            module: ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("Module").unwrap()),
+            function: Identifier::new("function").unwrap(),
+            ty_args: vec![Currency::XUS.type_tag()],
+            args_generator: Arc::new(|rng: &mut ::rand::rngs::StdRng| {
+                vec![bcs::to_bytes(&rng.gen::<u64>()).unwrap()]
+            }),
+        };
+        let args = (call.args_generator)(&mut rng);
+        let txn = gen_module_call_txn_request(
+            &mut sender,
+            &call,
+            args.clone(),
+            &TransactionFactory::new(ChainId::test()),
+        );
+
+        assert_eq!(txn.sender(), sender.address());
+        assert_eq!(txn.sequence_number(), 0);
+        assert_eq!(sender.sequence_number(), 1);
+        match txn.payload() {
+            TransactionPayload::ScriptFunction(script_function) => {
+                assert_eq!(script_function.module(), &call.module);
+                assert_eq!(script_function.function(), call.function.as_ident_str());
+                assert_eq!(script_function.ty_args(), call.ty_args.as_slice());
+                assert_eq!(script_function.args(), args.as_slice());
+            }
+            payload => panic!("Expected a script function payload, got {:?}", payload),
+        }
+        txn.check_signature().unwrap();
+    }
+
+    #[test]
+    fn test_gen_multi_agent_txn_request() {
+        let num_secondary_signers = 2;
+        let script = gen_multi_agent_script(num_secondary_signers);
+        let compiled_script = CompiledScript::deserialize(script.code()).unwrap();
+        bytecode_verifier::verify_script(&compiled_script).unwrap();
+        assert_eq!(
+            compiled_script.signatures[compiled_script.parameters.0 as usize].0,
+            vec![SignatureToken::Signer; num_secondary_signers + 1]
+        );
+
+        let mut rng = ::rand::rngs::StdRng::seed_from_u64(0);
+        let mut accounts = gen_random_accounts(4, &mut rng);
+        let txn = gen_multi_agent_txn_request(
+            &mut accounts,
+            1,
+            &[3, 0],
+            script,
+            &TransactionFactory::new(ChainId::test()),
+        );
+
+        assert_eq!(txn.sender(), accounts[1].address());
+        assert_eq!(
+            txn.authenticator().secondary_signer_addreses(),
+            vec![accounts[3].address(), accounts[0].address()]
+        );
+        // Only the sender's sequence number is consumed
+        let sequence_numbers = accounts
+            .iter()
+            .map(|a| a.sequence_number())
+            .collect::<Vec<_>>();
+        assert_eq!(sequence_numbers, vec![0, 1, 0, 0]);
+        txn.check_signature().unwrap();
+    }
+
+    #[test]
+    fn test_check_balances() {
+        let balances = vec![amount(100, Currency::XUS), amount(0, Currency::XDX)];
+        check_balances(&balances, &[Currency::XUS], 100).unwrap();
+        check_balances(&balances, &[Currency::XUS], 101).unwrap_err();
+        // Holding a currency without a balance in it is not enough
+        check_balances(&balances, &[Currency::XUS, Currency::XDX], 1).unwrap_err();
+        check_balances(&[amount(100, Currency::XUS)], &[Currency::XDX], 1).unwrap_err();
+    }
+}
@@ -9,6 +9,7 @@ use std::{
     io::Write,
     path::{Path, PathBuf},
     process::Command,
+    time::Duration,
 };
 
 use compiler::Compiler;
@@ -20,7 +21,7 @@ use diem_sdk::{
         transaction::{Module, Script, TransactionArgument, TransactionPayload},
     },
 };
-use forge::{AdminContext, AdminTest, Result, Test};
+use forge::{AdminAccount, AdminContext, AdminTest, Result, Test};
 
 pub struct MalformedScript;
 
@@ -222,16 +223,17 @@ fn enable_custom_script(ctx: &mut AdminContext) -> Result<()> {
             .expect("Failed to compile")
     };
 
-    let client = ctx.client();
-    let factory = ctx.chain_info().transaction_factory();
-    let txn =
-        ctx.chain_info()
-            .root_account
-            .sign_with_transaction_builder(factory.payload(TransactionPayload::Script(
-                Script::new(script_body, vec![], vec![]),
-            )));
-    client.submit(&txn)?;
-    client.wait_for_signed_transaction(&txn, None, None)?;
+    ctx.submit_admin_and_confirm(
+        AdminAccount::Root,
+        |factory| {
+            factory.payload(TransactionPayload::Script(Script::new(
+                script_body.clone(),
+                vec![],
+                vec![],
+            )))
+        },
+        Duration::from_secs(60),
+    )?;
     Ok(())
 }
 
@@ -8,6 +8,7 @@ use diem_sdk::{
     types::{account_config::XUS_NAME, transaction::authenticator::AuthenticationKey},
 };
 use forge::{PublicUsageContext, PublicUsageTest, Result, Test};
+use std::time::Duration;
 
 pub struct ExternalTransactionSigner;
 
@@ -66,7 +67,12 @@ impl PublicUsageTest for ExternalTransactionSigner {
         // submit the transaction
         let txn = SignedTransaction::new(unsigned_txn, public_key, signature);
         client.submit(&txn)?;
-        client.wait_for_signed_transaction(&txn, None, None)?;
+        // the transaction is committed once the sequence number of the sender has advanced
+        ctx.wait_for_sequence_number(
+            sender_address,
+            test_sequence_number + 1,
+            Duration::from_secs(60),
+        )?;
 
         // query the transaction and check it contains the same values as requested
         let txn = client
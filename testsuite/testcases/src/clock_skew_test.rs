@@ -0,0 +1,74 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::generate_traffic;
+use anyhow::anyhow;
+use forge::{ClockOffset, NetworkContext, NetworkTest, NodeExt, Result, SwarmExt, Test};
+use std::time::{Duration, Instant};
+
+/// How far the clock of the target validator is skewed ahead of the rest of the network.
+const CLOCK_OFFSET: ClockOffset = ClockOffset::Ahead(Duration::from_secs(30));
+
+/// Skews the clock of a single validator while traffic is generated against the rest of the
+/// network, and checks that the network neither forks under the skew nor fails to recover, with
+/// the target included, once the skew is removed.
+pub struct ClockSkew;
+
+impl Test for ClockSkew {
+    fn name(&self) -> &'static str {
+        "resilience::clock-skew"
+    }
+}
+
+impl NetworkTest for ClockSkew {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
+        let duration = Duration::from_secs(120);
+        let all_validators = ctx
+            .swarm()
+            .validators()
+            .map(|v| v.peer_id())
+            .collect::<Vec<_>>();
+        let (target, others) = all_validators
+            .split_first()
+            .filter(|(_, others)| !others.is_empty())
+            .ok_or_else(|| anyhow!("ClockSkew requires at least two validators"))?;
+
+        // The rest of the network is unaffected, so the traffic against it has to succeed, and
+        // the skewed target must not make it diverge
+        let validator = ctx.swarm().validator_mut(*target).unwrap();
+        let skew_result = validator.set_clock_offset(CLOCK_OFFSET);
+        let traffic_result = match &skew_result {
+            Ok(()) => Some(
+                generate_traffic(ctx, others, duration).and_then(|txn_stat| {
+                    ctx.swarm().fork_check()?;
+                    Ok(txn_stat)
+                }),
+            ),
+            Err(_) => None,
+        };
+
+        // Always remove the skew again, even if skewing the clock or the traffic failed, as
+        // setting the offset may have taken effect partially
+        let validator = ctx.swarm().validator_mut(*target).unwrap();
+        let cleanup_result = validator
+            .clear_clock_offset()
+            .and_then(|()| validator.wait_until_healthy(Instant::now() + Duration::from_secs(120)));
+
+        // Report the root cause first, a failed cleanup is likely a consequence of it
+        skew_result?;
+        let txn_stat = traffic_result.expect("traffic is generated once the clock is skewed")?;
+        cleanup_result?;
+        ctx.report
+            .report_txn_stats(self.name().to_string(), txn_stat, duration);
+
+        // Once the skew is removed, the target has to catch up and the whole network, including
+        // it, has to make progress again without forking
+        ctx.swarm()
+            .wait_for_all_nodes_to_catchup(Instant::now() + Duration::from_secs(120))?;
+        generate_traffic(ctx, &all_validators, Duration::from_secs(30))?;
+        ctx.swarm().fork_check()?;
+        ctx.report.print_report();
+
+        Ok(())
+    }
+}
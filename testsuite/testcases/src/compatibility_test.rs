@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{batch_update, generate_traffic};
-use anyhow::bail;
+use anyhow::{bail, ensure};
 use forge::{NetworkContext, NetworkTest, Result, SwarmExt, Test};
+use std::time::Instant;
 use tokio::time::Duration;
 
 pub struct SimpleValidatorUpgrade;
@@ -44,6 +45,7 @@ impl NetworkTest for SimpleValidatorUpgrade {
         let duration = Duration::from_secs(5);
 
         println!("1. Downgrade all validators to older version");
+        let phase_start = Instant::now();
         // Ensure that all validators are running the older version of the software
         let validators_to_downgrade = ctx
             .swarm()
@@ -55,27 +57,56 @@ impl NetworkTest for SimpleValidatorUpgrade {
 
         // Generate some traffic
         generate_traffic(ctx, &all_validators, duration)?;
+        report_phase_duration(ctx, self.name(), "downgrade", phase_start);
 
         // Update the first Validator
         println!("2. upgrading first Validator");
+        let phase_start = Instant::now();
         batch_update(ctx, &[first_node], &new_version)?;
         generate_traffic(ctx, &[first_node], duration)?;
+        report_phase_duration(ctx, self.name(), "first_node_upgrade", phase_start);
 
         // Update the rest of the first batch
         println!("3. upgrading rest of first batch");
+        let phase_start = Instant::now();
         batch_update(ctx, &first_batch, &new_version)?;
         generate_traffic(ctx, &first_batch, duration)?;
 
         ctx.swarm().fork_check()?;
+        report_phase_duration(ctx, self.name(), "first_batch_upgrade", phase_start);
 
         // Update the second batch
         println!("4. upgrading second batch");
+        let phase_start = Instant::now();
         batch_update(ctx, &second_batch, &new_version)?;
         generate_traffic(ctx, &second_batch, duration)?;
+        report_phase_duration(ctx, self.name(), "second_batch_upgrade", phase_start);
 
         println!("5. check swarm health");
+        let phase_start = Instant::now();
         ctx.swarm().fork_check()?;
+        ctx.for_each_validator(|validator| {
+            ensure!(
+                validator.version() == new_version,
+                "{} still runs {} after the upgrade to {}",
+                validator.name(),
+                validator.version(),
+                new_version
+            );
+            Ok(())
+        })?;
+        report_phase_duration(ctx, self.name(), "health_check", phase_start);
+        ctx.report.print_report();
 
         Ok(())
     }
 }
+
+/// Reports how long the phase `phase` of the test `test_name`, started at `start`, took.
+fn report_phase_duration(ctx: &mut NetworkContext, test_name: &str, phase: &str, start: Instant) {
+    let elapsed = start.elapsed().as_secs_f64();
+    ctx.report
+        .report_metric(test_name, format!("{}_duration_secs", phase), elapsed);
+    ctx.report
+        .report_text(format!("{} : {} took {:.1}s", test_name, phase, elapsed));
+}
@@ -0,0 +1,74 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::generate_traffic;
+use anyhow::{anyhow, bail};
+use forge::{NetworkContext, NetworkTest, Node, NodeExt, Result, SwarmExt, Test};
+use std::time::{Duration, Instant};
+
+/// Free space to leave on the target validator's data volume, so that it is close to, but not
+/// completely at, capacity.
+const DISK_FREE_MARGIN_BYTES: u64 = 512 * 1024 * 1024;
+
+pub struct DiskPressure;
+
+impl Test for DiskPressure {
+    fn name(&self) -> &'static str {
+        "resilience::disk-pressure"
+    }
+}
+
+impl NetworkTest for DiskPressure {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
+        let duration = Duration::from_secs(120);
+        let all_validators = ctx
+            .swarm()
+            .validators()
+            .map(|v| v.peer_id())
+            .collect::<Vec<_>>();
+        let (target, others) = all_validators
+            .split_first()
+            .ok_or_else(|| anyhow!("DiskPressure requires at least one validator"))?;
+
+        let validator = ctx.swarm().validator_mut(*target).unwrap();
+        let free = validator.free_disk()?;
+        if free <= DISK_FREE_MARGIN_BYTES {
+            bail!(
+                "{} has only {} bytes free, not enough to apply disk pressure",
+                validator.name(),
+                free
+            );
+        }
+
+        // Fill up the target validator's disk and generate traffic against the rest of the
+        // network. The target is allowed to fall behind or crash, but must not corrupt its state.
+        // The rest of the network is unaffected, so the traffic against it has to succeed.
+        let pressure_result = validator.consume_disk(free - DISK_FREE_MARGIN_BYTES);
+        let traffic_result = match &pressure_result {
+            Ok(()) => Some(generate_traffic(ctx, others, duration)),
+            Err(_) => None,
+        };
+
+        // Always release the space again, even if filling the disk or the traffic failed
+        let validator = ctx.swarm().validator_mut(*target).unwrap();
+        let cleanup_result = validator.release_disk().and_then(|()| {
+            Node::restart(validator)?;
+            validator.wait_until_healthy(Instant::now() + Duration::from_secs(120))
+        });
+
+        // Report the root cause first, a failed cleanup is likely a consequence of it
+        pressure_result?;
+        let txn_stat = traffic_result.expect("traffic is generated once the disk is filled")?;
+        cleanup_result?;
+        ctx.report
+            .report_txn_stats(self.name().to_string(), txn_stat, duration);
+
+        // Ensure the target recovered without diverging from the rest of the network
+        ctx.swarm()
+            .wait_for_all_nodes_to_catchup(Instant::now() + Duration::from_secs(120))?;
+        ctx.swarm().fork_check()?;
+        ctx.report.print_report();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail};
+use diem_sdk::{
+    client::BlockingClient,
+    types::{account_config::diem_root_address, on_chain_config::ConfigurationResource, PeerId},
+};
+use forge::{
+    EmitJobRequest, NetworkContext, NetworkTest, NodeExt, Result, SwarmExt, Test, TxnEmitter,
+    TxnStats,
+};
+use rand::SeedableRng;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::runtime::Runtime;
+
+/// Removes a validator from the validator set while traffic is being generated against the
+/// remaining validators, and checks that the epoch change neither loses transactions nor forks
+/// the network. The validator is added back to the validator set afterwards.
+pub struct EpochTransitionUnderLoad;
+
+impl Test for EpochTransitionUnderLoad {
+    fn name(&self) -> &'static str {
+        "epoch-transition-under-load"
+    }
+}
+
+impl NetworkTest for EpochTransitionUnderLoad {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
+        let duration = Duration::from_secs(120);
+        let all_validators = ctx
+            .swarm()
+            .validators()
+            .map(|v| v.peer_id())
+            .collect::<Vec<_>>();
+        let (target, others) = all_validators
+            .split_last()
+            .filter(|(_, others)| !others.is_empty())
+            .ok_or_else(|| anyhow!("EpochTransitionUnderLoad requires at least two validators"))?;
+        let start_epoch = epoch(&ctx.swarm().validator(*target).unwrap().json_rpc_client())?;
+
+        // Start emitting to the validators which stay in the validator set. The emitter borrows
+        // the swarm, so it is dropped while the job runs and a new one stops the job later.
+        let rt = Runtime::new()?;
+        let rng = SeedableRng::from_rng(ctx.core().rng())?;
+        let validator_clients = ctx
+            .swarm()
+            .validators()
+            .filter(|v| others.contains(&v.peer_id()))
+            .map(|n| n.async_json_rpc_client())
+            .collect::<Vec<_>>();
+        let emit_job_request = EmitJobRequest::default(validator_clients);
+        let job = {
+            let mut emitter = TxnEmitter::new(ctx.swarm().chain_info(), rng);
+            rt.block_on(emitter.start_job(emit_job_request))?
+        };
+
+        // Reconfigure halfway through the traffic
+        thread::sleep(duration / 2);
+        let reconfig_result = ctx.swarm().demote_to_standby(*target);
+        thread::sleep(duration / 2);
+
+        let rng = SeedableRng::from_rng(ctx.core().rng())?;
+        let txn_stats = {
+            let mut emitter = TxnEmitter::new(ctx.swarm().chain_info(), rng);
+            rt.block_on(emitter.stop_job(job))
+        };
+        let check_result =
+            reconfig_result.and_then(|()| check_epoch_change(ctx, start_epoch, &txn_stats));
+
+        // Always restore the validator set for subsequent tests, even if the epoch change failed
+        let restore_result = ctx.swarm().promote_to_validator(*target);
+        // Report the root cause first, a failed restore is likely a consequence of it
+        check_result?;
+        restore_result?;
+
+        ctx.report
+            .report_txn_stats(self.name().to_string(), txn_stats, duration);
+        ctx.report.print_report();
+
+        Ok(())
+    }
+}
+
+/// Checks that no transaction expired across the epoch change which started at `start_epoch`,
+/// and that all validators moved on to the same new epoch without forking.
+fn check_epoch_change(
+    ctx: &mut NetworkContext,
+    start_epoch: u64,
+    txn_stats: &TxnStats,
+) -> Result<()> {
+    if txn_stats.expired > 0 {
+        bail!(
+            "{} of {} transactions expired across the epoch change",
+            txn_stats.expired,
+            txn_stats.submitted
+        );
+    }
+
+    // All remaining validators must have moved past the starting epoch, to the same epoch
+    ctx.swarm()
+        .wait_for_all_nodes_to_catchup(Instant::now() + Duration::from_secs(60))?;
+    let epochs = ctx
+        .swarm()
+        .validators()
+        .map(|v| Ok((v.peer_id(), epoch(&v.json_rpc_client())?)))
+        .collect::<Result<Vec<(PeerId, u64)>>>()?;
+    if epochs.iter().any(|(_, epoch)| *epoch <= start_epoch)
+        || epochs.windows(2).any(|w| w[0].1 != w[1].1)
+    {
+        bail!(
+            "Validators did not agree on a new epoch after epoch {}: {:?}",
+            start_epoch,
+            epochs
+        );
+    }
+    ctx.swarm().fork_check()
+}
+
+/// Returns the current epoch as seen by the node behind `client`
+fn epoch(client: &BlockingClient) -> Result<u64> {
+    let configuration = client
+        .get_deserialized_resource::<ConfigurationResource>(diem_root_address())?
+        .into_inner()
+        .ok_or_else(|| anyhow!("No configuration resource found"))?;
+    Ok(configuration.epoch())
+}
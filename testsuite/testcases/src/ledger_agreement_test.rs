@@ -0,0 +1,49 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::generate_traffic;
+use anyhow::{anyhow, ensure};
+use forge::{NetworkContext, NetworkTest, Result, SwarmExt, Test};
+use std::time::Duration;
+
+/// Generates traffic against all validators, takes a coordinated snapshot of them once they have
+/// quiesced, and checks that they agree on the ledger state both at genesis and at the snapshot.
+pub struct LedgerAgreement;
+
+impl Test for LedgerAgreement {
+    fn name(&self) -> &'static str {
+        "safety::ledger-agreement"
+    }
+}
+
+impl NetworkTest for LedgerAgreement {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
+        let duration = Duration::from_secs(30);
+        let all_validators = ctx
+            .swarm()
+            .validators()
+            .map(|v| v.peer_id())
+            .collect::<Vec<_>>();
+        let txn_stat = generate_traffic(ctx, &all_validators, duration)?;
+        ctx.report
+            .report_txn_stats(self.name().to_string(), txn_stat, duration);
+
+        // The traffic has already stopped, so there is nothing to pause before the snapshot
+        let snapshots = ctx.swarm().coordinated_snapshot(|| Ok(()))?;
+        let snapshot = snapshots
+            .first()
+            .ok_or_else(|| anyhow!("Snapshot contains no validators"))?;
+        ensure!(
+            snapshots
+                .iter()
+                .all(|s| s.accumulator_root_hash == snapshot.accumulator_root_hash),
+            "Validators disagree on the ledger state at snapshot version {}",
+            snapshot.version
+        );
+        ctx.swarm().assert_agreement_at(0)?;
+        ctx.swarm().assert_agreement_at(snapshot.version)?;
+        ctx.report.print_report();
+
+        Ok(())
+    }
+}
@@ -1,13 +1,29 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod clock_skew_test;
 pub mod compatibility_test;
+pub mod disk_pressure_test;
+pub mod epoch_transition_test;
+pub mod ledger_agreement_test;
 pub mod performance_test;
+pub mod validator_failover_test;
 
+use debug_interface::NodeDebugClient;
 use diem_sdk::types::PeerId;
-use forge::{EmitJobRequest, NetworkContext, NodeExt, Result, TxnEmitter, TxnStats, Version};
+use forge::{
+    mempool_size, EmitJobRequest, MempoolSizeStats, NetworkContext, NodeExt, Result,
+    TransactionType, TxnEmitter, TxnStats, Version,
+};
 use rand::SeedableRng;
-use std::time::{Duration, Instant};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 use tokio::runtime::Runtime;
 
 fn batch_update<'t>(
@@ -34,6 +50,17 @@ pub fn generate_traffic<'t>(
     ctx: &mut NetworkContext<'t>,
     validators: &[PeerId],
     duration: Duration,
+) -> Result<TxnStats> {
+    generate_traffic_of_type(ctx, validators, duration, TransactionType::p2p())
+}
+
+/// Like `generate_traffic`, but emits transactions of the given type, e.g. calls of a script
+/// function of a published module to load-test module execution.
+pub fn generate_traffic_of_type<'t>(
+    ctx: &mut NetworkContext<'t>,
+    validators: &[PeerId],
+    duration: Duration,
+    transaction_type: TransactionType,
 ) -> Result<TxnStats> {
     let rt = Runtime::new()?;
     let rng = SeedableRng::from_rng(ctx.core().rng())?;
@@ -43,9 +70,56 @@ pub fn generate_traffic<'t>(
         .filter(|v| validators.contains(&v.peer_id()))
         .map(|n| n.async_json_rpc_client())
         .collect::<Vec<_>>();
+    let account_cache = ctx.emitter_account_cache().map(|path| path.to_path_buf());
     let mut emitter = TxnEmitter::new(ctx.swarm().chain_info(), rng);
-    let stats =
-        rt.block_on(emitter.emit_txn_for(duration, EmitJobRequest::default(validator_clients)))?;
+    if let Some(path) = account_cache {
+        emitter = emitter.with_account_cache(path);
+    }
+    let mut emit_job_request = EmitJobRequest::default(validator_clients);
+    emit_job_request.circuit_breaker = ctx.emitter_circuit_breaker();
+    emit_job_request.transaction_type = transaction_type;
+    let stats = rt.block_on(emitter.emit_txn_for(duration, emit_job_request))?;
 
     Ok(stats)
 }
+
+/// Like `generate_traffic`, but also samples the mempool size of each of the `validators` every
+/// `interval` while the traffic is generated, to tell a saturated mempool apart from an emitter
+/// which doesn't submit fast enough.
+pub fn generate_traffic_sampling_mempool<'t>(
+    ctx: &mut NetworkContext<'t>,
+    validators: &[PeerId],
+    duration: Duration,
+    interval: Duration,
+) -> Result<(TxnStats, MempoolSizeStats)> {
+    let debug_clients = ctx
+        .swarm()
+        .validators()
+        .filter(|v| validators.contains(&v.peer_id()))
+        .map(|v| NodeDebugClient::from_url(v.debug_endpoint()))
+        .collect::<Vec<_>>();
+    let stop = Arc::new(AtomicBool::new(false));
+    let sampler = {
+        let stop = stop.clone();
+        thread::spawn(move || {
+            let mut samples = vec![];
+            while !stop.load(Ordering::Relaxed) {
+                // A failed sample, e.g. of a validator which is restarting, is just skipped
+                samples.extend(
+                    debug_clients
+                        .iter()
+                        .filter_map(|client| mempool_size(client).ok().flatten()),
+                );
+                thread::sleep(interval);
+            }
+            samples
+        })
+    };
+
+    let result = generate_traffic(ctx, validators, duration);
+    stop.store(true, Ordering::Relaxed);
+    let samples = sampler
+        .join()
+        .map_err(|_| anyhow::anyhow!("Mempool sampler thread panicked"))?;
+    Ok((result?, MempoolSizeStats::from_samples(&samples)))
+}
@@ -1,11 +1,34 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::generate_traffic;
-use forge::{NetworkContext, NetworkTest, Result, Test};
+use crate::generate_traffic_of_type;
+use diem_sdk::{
+    move_types::{
+        identifier::Identifier,
+        language_storage::{ModuleId, CORE_CODE_ADDRESS},
+    },
+    transaction_builder::Currency,
+    types::account_config::testnet_dd_account_address,
+};
+use forge::{
+    write_block_timeline_csv, ModuleCall, NetworkContext, NetworkTest, NodeExt, Result, Test,
+    TransactionType,
+};
+use rand::Rng;
+use std::{fs::File, io::BufWriter, sync::Arc};
 use tokio::time::Duration;
 
-pub struct PerformanceBenchmark;
+pub struct PerformanceBenchmark {
+    /// The currencies of the benchmark's transfers, the traffic is split evenly among them.
+    pub currencies: &'static [Currency],
+}
+
+impl PerformanceBenchmark {
+    /// Benchmarks transfers of XUS.
+    pub const XUS: Self = Self {
+        currencies: &[Currency::XUS],
+    };
+}
 
 impl Test for PerformanceBenchmark {
     fn name(&self) -> &'static str {
@@ -21,9 +44,107 @@ impl NetworkTest for PerformanceBenchmark {
             .validators()
             .map(|v| v.peer_id())
             .collect::<Vec<_>>();
+        let start_version = ledger_version(ctx)?;
 
         // Generate some traffic
-        let txn_stat = generate_traffic(ctx, &all_validators, duration)?;
+        let txn_stat = generate_traffic_of_type(
+            ctx,
+            &all_validators,
+            duration,
+            TransactionType::P2P(self.currencies.to_vec()),
+        )?;
+        ctx.report
+            .report_txn_stats(self.name().to_string(), txn_stat, duration);
+        export_block_timeline(ctx, self.name(), start_version)?;
+        ctx.report.print_report();
+
+        Ok(())
+    }
+}
+
+fn ledger_version(ctx: &mut NetworkContext) -> Result<u64> {
+    let validator = ctx
+        .swarm()
+        .validators()
+        .next()
+        .expect("swarm has no validators");
+    Ok(validator
+        .json_rpc_client()
+        .get_metadata()?
+        .into_inner()
+        .version)
+}
+
+/// If the run asks for block timelines, writes the timeline of the blocks committed since
+/// `start_version` to `<test_name>.csv` in the requested directory.
+fn export_block_timeline(
+    ctx: &mut NetworkContext,
+    test_name: &str,
+    start_version: u64,
+) -> Result<()> {
+    let dir = match ctx.block_timeline_dir() {
+        Some(dir) => dir.to_path_buf(),
+        None => return Ok(()),
+    };
+    let end_version = ledger_version(ctx)? + 1;
+    let validator = ctx
+        .swarm()
+        .validators()
+        .next()
+        .expect("swarm has no validators");
+    let timeline = validator.block_timeline(start_version..end_version)?;
+    let path = dir.join(format!("{}.csv", test_name.replace("::", "-")));
+    write_block_timeline_csv(&timeline, &mut BufWriter::new(File::create(&path)?))?;
+    ctx.report.report_text(format!(
+        "{} : block timeline of {} blocks written to {}",
+        test_name,
+        timeline.len(),
+        path.display()
+    ));
+    Ok(())
+}
+
+/// Benchmarks calls of a script function, to load-test module execution rather than the transfer
+/// path. Each call pays 1 XUS to the designated dealer with random metadata, so that the arguments
+/// vary across transactions.
+pub struct ModuleCallBenchmark;
+
+impl Test for ModuleCallBenchmark {
+    fn name(&self) -> &'static str {
+        "performance::module-call-benchmark"
+    }
+}
+
+impl NetworkTest for ModuleCallBenchmark {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
+        let duration = Duration::from_secs(240);
+        let all_validators = ctx
+            .swarm()
+            .validators()
+            .map(|v| v.peer_id())
+            .collect::<Vec<_>>();
+
+        let call = ModuleCall {
+            module: ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("PaymentScripts")?),
+            function: Identifier::new("peer_to_peer_with_metadata")?,
+            ty_args: vec![Currency::XUS.type_tag()],
+            args_generator: Arc::new(|rng: &mut ::rand::rngs::StdRng| {
+                let metadata_len = rng.gen_range(0..32);
+                let metadata = (0..metadata_len).map(|_| rng.gen()).collect::<Vec<u8>>();
+                vec![
+                    bcs::to_bytes(&testnet_dd_account_address()).unwrap(),
+                    bcs::to_bytes(&1u64).unwrap(),
+                    bcs::to_bytes(&metadata).unwrap(),
+                    bcs::to_bytes(&Vec::<u8>::new()).unwrap(),
+                ]
+            }),
+        };
+        let txn_stat = generate_traffic_of_type(
+            ctx,
+            &all_validators,
+            duration,
+            TransactionType::ModuleCall(call),
+        )?;
         ctx.report
             .report_txn_stats(self.name().to_string(), txn_stat, duration);
         ctx.report.print_report();
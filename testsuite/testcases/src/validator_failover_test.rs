@@ -0,0 +1,93 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::generate_traffic;
+use anyhow::{anyhow, bail};
+use diem_sdk::types::PeerId;
+use forge::{NetworkContext, NetworkTest, NodeExt, Result, SwarmExt, Test};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Kills an active validator and replaces it in the validator set with a standby validator, as
+/// an operator would, and reports how long it took from the kill until the promoted validator
+/// is live. Requires a swarm with at least one standby validator, e.g. a k8s swarm created with
+/// `--num-standby-validators 1`. The killed validator is left as a standby, so that subsequent
+/// tests see as many validators as before.
+pub struct ValidatorFailover;
+
+impl Test for ValidatorFailover {
+    fn name(&self) -> &'static str {
+        "resilience::validator-failover"
+    }
+}
+
+impl NetworkTest for ValidatorFailover {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
+        let duration = Duration::from_secs(60);
+        let standby = ctx
+            .swarm()
+            .standby_validators()
+            .map(|v| v.peer_id())
+            .next()
+            .ok_or_else(|| anyhow!("ValidatorFailover requires a standby validator"))?;
+        let all_validators = ctx
+            .swarm()
+            .validators()
+            .map(|v| v.peer_id())
+            .collect::<Vec<_>>();
+        let (victim, others) = all_validators
+            .split_first()
+            .filter(|(_, others)| !others.is_empty())
+            .ok_or_else(|| anyhow!("ValidatorFailover requires at least two validators"))?;
+
+        let failover_start = Instant::now();
+        ctx.swarm().validator_mut(*victim).unwrap().kill()?;
+        ctx.swarm().demote_to_standby(*victim)?;
+        if let Err(e) = ctx.swarm().promote_to_validator(standby) {
+            // Keep the validator set at its size for subsequent tests
+            ctx.swarm().promote_to_validator(*victim)?;
+            return Err(e);
+        }
+        wait_until_live(ctx, standby, Instant::now() + Duration::from_secs(300))?;
+        let failover_secs = failover_start.elapsed().as_secs_f64();
+        ctx.report
+            .report_metric(self.name(), "failover_duration_secs", failover_secs);
+        ctx.report.report_text(format!(
+            "{} : replaced {} with {} in {:.1}s",
+            self.name(),
+            victim,
+            standby,
+            failover_secs
+        ));
+
+        // The network, including the promoted validator, must make progress without forking
+        let mut live_validators = others.to_vec();
+        live_validators.push(standby);
+        let txn_stat = generate_traffic(ctx, &live_validators, duration)?;
+        ctx.report
+            .report_txn_stats(self.name().to_string(), txn_stat, duration);
+        ctx.swarm()
+            .wait_for_all_nodes_to_catchup(Instant::now() + Duration::from_secs(60))?;
+        ctx.swarm().fork_check()?;
+        ctx.report.print_report();
+
+        Ok(())
+    }
+}
+
+/// Waits until the validator `id` has synced and commits blocks along with the network.
+fn wait_until_live(ctx: &mut NetworkContext, id: PeerId, deadline: Instant) -> Result<()> {
+    let validator = ctx
+        .swarm()
+        .validator(id)
+        .ok_or_else(|| anyhow!("{} is not an active validator", id))?;
+    while Instant::now() < deadline {
+        if validator.liveness_check(10).is_ok() {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+    bail!("Timed out waiting for {} to be live", validator.name())
+}
@@ -0,0 +1,36 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use diem_config::config::NodeConfig;
+use diem_logger::Level;
+use forge::{forge_main, ForgeConfig, InitialVersion, LocalFactory, Options, Result};
+use std::num::NonZeroUsize;
+use testcases::ledger_agreement_test::LedgerAgreement;
+
+const NUM_VALIDATORS: usize = 4;
+
+fn main() -> Result<()> {
+    ::diem_logger::Logger::init_for_testing();
+
+    // Give each validator a different max block size, so that the validators have to agree on
+    // blocks proposed under different limits
+    let node_configs = (0..NUM_VALIDATORS)
+        .map(|i| {
+            let mut config = NodeConfig::default_for_validator();
+            config.randomize_ports();
+            config.consensus.max_block_size = 100 * (i as u64 + 1);
+            config
+        })
+        .collect();
+
+    let tests = ForgeConfig::default()
+        .with_initial_validator_count(NonZeroUsize::new(NUM_VALIDATORS).unwrap())
+        .with_initial_version(InitialVersion::Newest)
+        .with_network_tests(&[&LedgerAgreement]);
+
+    let options = Options::from_args();
+    // Config-dependent failures are hard to diagnose from the default logs, so run the nodes at
+    // debug level
+    let factory = LocalFactory::from_configs(node_configs)?.with_node_log_level(Level::Debug);
+    forge_main(tests, factory, &options)
+}
@@ -3,7 +3,7 @@
 
 use forge::{forge_main, ForgeConfig, InitialVersion, LocalFactory, Options, Result};
 use std::num::NonZeroUsize;
-use testcases::performance_test::PerformanceBenchmark;
+use testcases::performance_test::{ModuleCallBenchmark, PerformanceBenchmark};
 
 fn main() -> Result<()> {
     ::diem_logger::Logger::init_for_testing();
@@ -11,7 +11,7 @@ fn main() -> Result<()> {
     let tests = ForgeConfig::default()
         .with_initial_validator_count(NonZeroUsize::new(4).unwrap())
         .with_initial_version(InitialVersion::Newest)
-        .with_network_tests(&[&PerformanceBenchmark]);
+        .with_network_tests(&[&PerformanceBenchmark::XUS, &ModuleCallBenchmark]);
 
     let options = Options::from_args();
     forge_main(tests, LocalFactory::from_workspace()?, &options)
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    account_address::AccountAddress,
+    account_address::{self, AccountAddress},
     account_config::XUS_NAME,
     chain_id::ChainId,
     transaction::{
@@ -27,6 +27,31 @@ fn expiration_time(seconds: u64) -> u64 {
         + seconds
 }
 
+/// Builds an unsigned transaction with every field given explicitly, without any defaults. This
+/// is meant for fuzzing deserialization and validation, where boundary values (e.g. a
+/// `max_gas_amount` of `u64::MAX`) need to be generated. The other helpers are built on top of it.
+pub fn build_raw_txn(
+    sender: AccountAddress,
+    sequence_number: u64,
+    payload: TransactionPayload,
+    max_gas_amount: u64,
+    gas_unit_price: u64,
+    gas_currency_code: String,
+    expiration_timestamp_secs: u64,
+    chain_id: ChainId,
+) -> RawTransaction {
+    RawTransaction::new(
+        sender,
+        sequence_number,
+        payload,
+        max_gas_amount,
+        gas_unit_price,
+        gas_currency_code,
+        expiration_timestamp_secs,
+        chain_id,
+    )
+}
+
 // Test helper for transaction creation
 pub fn get_test_signed_module_publishing_transaction(
     sender: AccountAddress,
@@ -36,10 +61,10 @@ pub fn get_test_signed_module_publishing_transaction(
     module: Module,
 ) -> SignedTransaction {
     let expiration_time = expiration_time(10);
-    let raw_txn = RawTransaction::new_module(
+    let raw_txn = build_raw_txn(
         sender,
         sequence_number,
-        module,
+        TransactionPayload::Module(module),
         MAX_GAS_AMOUNT,
         TEST_GAS_PRICE,
         XUS_NAME.to_owned(),
@@ -64,10 +89,12 @@ pub fn get_test_signed_transaction(
     gas_currency_code: String,
     max_gas_amount: Option<u64>,
 ) -> SignedTransaction {
-    let raw_txn = RawTransaction::new_script(
+    let raw_txn = build_raw_txn(
         sender,
         sequence_number,
-        script.unwrap_or_else(|| Script::new(EMPTY_SCRIPT.to_vec(), vec![], Vec::new())),
+        TransactionPayload::Script(
+            script.unwrap_or_else(|| Script::new(EMPTY_SCRIPT.to_vec(), vec![], Vec::new())),
+        ),
         max_gas_amount.unwrap_or(MAX_GAS_AMOUNT),
         gas_unit_price,
         gas_currency_code,
@@ -119,10 +146,12 @@ fn get_test_unchecked_transaction_(
     max_gas_amount: Option<u64>,
     chain_id: ChainId,
 ) -> SignedTransaction {
-    let raw_txn = RawTransaction::new_script(
+    let raw_txn = build_raw_txn(
         sender,
         sequence_number,
-        script.unwrap_or_else(|| Script::new(EMPTY_SCRIPT.to_vec(), vec![], Vec::new())),
+        TransactionPayload::Script(
+            script.unwrap_or_else(|| Script::new(EMPTY_SCRIPT.to_vec(), vec![], Vec::new())),
+        ),
         max_gas_amount.unwrap_or(MAX_GAS_AMOUNT),
         gas_unit_price,
         gas_currency_code,
@@ -190,7 +219,7 @@ pub fn get_test_unchecked_multi_agent_txn(
     script: Option<Script>,
 ) -> SignedTransaction {
     let expiration_time = expiration_time(10);
-    let raw_txn = RawTransaction::new(
+    let raw_txn = build_raw_txn(
         sender,
         sequence_number,
         TransactionPayload::Script(
@@ -225,6 +254,42 @@ pub fn get_test_unchecked_multi_agent_txn(
     )
 }
 
+/// Like `get_test_unchecked_multi_agent_txn`, but with `num_secondary_signers` secondary signers
+/// whose keys are freshly generated. Returns the transaction along with the private keys of the
+/// secondary signers, in the order of the transaction's secondary signer addresses.
+pub fn get_test_multi_agent_txn_n(
+    sender: AccountAddress,
+    sequence_number: u64,
+    sender_private_key: &Ed25519PrivateKey,
+    sender_public_key: Ed25519PublicKey,
+    num_secondary_signers: usize,
+    script: Option<Script>,
+) -> (SignedTransaction, Vec<Ed25519PrivateKey>) {
+    let mut rng = rand::rngs::OsRng;
+    let secondary_private_keys = (0..num_secondary_signers)
+        .map(|_| Ed25519PrivateKey::generate(&mut rng))
+        .collect::<Vec<_>>();
+    let secondary_public_keys = secondary_private_keys
+        .iter()
+        .map(Ed25519PublicKey::from)
+        .collect::<Vec<_>>();
+    let secondary_signers = secondary_public_keys
+        .iter()
+        .map(account_address::from_public_key)
+        .collect();
+    let txn = get_test_unchecked_multi_agent_txn(
+        sender,
+        secondary_signers,
+        sequence_number,
+        sender_private_key,
+        sender_public_key,
+        secondary_private_keys.iter().collect(),
+        secondary_public_keys,
+        script,
+    );
+    (txn, secondary_private_keys)
+}
+
 pub fn get_test_txn_with_chain_id(
     sender: AccountAddress,
     sequence_number: u64,
@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    account_address::AccountAddress,
+    account_address::{self, AccountAddress},
     account_config::XUS_NAME,
     chain_id::ChainId,
+    test_helpers::transaction_test_helpers::get_test_multi_agent_txn_n,
     transaction::{
         metadata, AccountTransactionsWithProof, GovernanceRole, RawTransaction, Script,
         SignedTransaction, Transaction, TransactionInfo, TransactionListWithProof,
@@ -39,6 +40,30 @@ fn test_invalid_signature() {
         .expect_err("signature checking should fail");
 }
 
+#[test]
+fn test_multi_agent_txn_with_generated_secondary_signers() {
+    let sender_private_key = Ed25519PrivateKey::generate_for_testing();
+    let (txn, secondary_private_keys) = get_test_multi_agent_txn_n(
+        AccountAddress::random(),
+        0,
+        &sender_private_key,
+        sender_private_key.public_key(),
+        5,
+        None,
+    );
+    assert_eq!(secondary_private_keys.len(), 5);
+    let secondary_signers = txn.authenticator().secondary_signer_addreses();
+    assert_eq!(
+        secondary_signers,
+        secondary_private_keys
+            .iter()
+            .map(|key| account_address::from_public_key(&key.public_key()))
+            .collect::<Vec<_>>()
+    );
+    txn.check_signature()
+        .expect("signature checking should succeed");
+}
+
 #[test]
 fn test_role_ordering() {
     use GovernanceRole::*;